@@ -0,0 +1,77 @@
+use super::MultiStash;
+use alloc::vec::Vec;
+use core::num::NonZeroUsize;
+
+/// Allows to configure and construct a [`MultiStash`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MultiStashBuilder {
+    /// The initial capacity of the [`MultiStash`].
+    capacity: usize,
+    /// The maximum amount of items a single element may hold if any.
+    max_per_entry: Option<NonZeroUsize>,
+    /// The number of entries to grow by when full if any.
+    growth_increment: Option<NonZeroUsize>,
+}
+
+impl MultiStashBuilder {
+    /// Creates a new [`MultiStashBuilder`] with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the initial capacity of the [`MultiStash`].
+    ///
+    /// See [`MultiStash::with_capacity`] for more information.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the maximum amount of items a single element of the [`MultiStash`] may hold.
+    ///
+    /// By default the amount of items per element is unbounded.
+    /// Operations that would exceed this maximum panic.
+    /// Use [`MultiStash::try_put`] and [`MultiStash::try_bump`] to handle this gracefully.
+    pub fn with_max_per_entry(mut self, max: NonZeroUsize) -> Self {
+        self.max_per_entry = Some(max);
+        self
+    }
+
+    /// Sets the number of entries the [`MultiStash`] grows by when it is full.
+    ///
+    /// By default the [`MultiStash`] grows geometrically like a [`Vec`].
+    /// With a growth increment the capacity instead grows linearly by multiples of
+    /// `increment` entries which makes memory usage predictable. This applies to all
+    /// operations that append entries, e.g. [`MultiStash::put`], [`MultiStash::insert_at`]
+    /// and [`MultiStash::merge_with`]. Explicit reservations such as [`MultiStash::reserve`]
+    /// or [`MultiStash::extend_exact`] are unaffected.
+    ///
+    /// # Note
+    ///
+    /// Linear growth reallocates more frequently than geometric growth so that
+    /// inserting `n` elements costs O(n^2 / increment) instead of amortized O(n).
+    pub fn with_growth_increment(mut self, increment: NonZeroUsize) -> Self {
+        self.growth_increment = Some(increment);
+        self
+    }
+
+    /// Constructs a new, empty [`MultiStash`] with the configuration of the [`MultiStashBuilder`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn build<T>(&self) -> MultiStash<T> {
+        MultiStash {
+            free: 0,
+            len_items: 0,
+            len_occupied: 0,
+            min_occupied: None,
+            max_occupied: None,
+            max_per_entry: self.max_per_entry,
+            growth_increment: self.growth_increment,
+            #[cfg(feature = "stats")]
+            stats: crate::Stats::new(),
+            entries: Vec::with_capacity(self.capacity),
+        }
+    }
+}
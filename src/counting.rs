@@ -0,0 +1,108 @@
+use super::{IndexedByItem, Iter, Key, MultiStash};
+use core::hash::Hash;
+use core::num::NonZeroUsize;
+
+/// A counting multiset built on top of an [`IndexedByItem`].
+///
+/// Equal items share a single entry whose remaining items count their occurrences.
+#[derive(Debug)]
+pub struct CountingStash<T> {
+    /// The underlying [`IndexedByItem`] storing the items and their counts.
+    indexed: IndexedByItem<T>,
+}
+
+impl<T: Clone> Clone for CountingStash<T> {
+    fn clone(&self) -> Self {
+        Self {
+            indexed: self.indexed.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.indexed.clone_from(&source.indexed);
+    }
+}
+
+impl<T> Default for CountingStash<T> {
+    fn default() -> Self {
+        Self {
+            indexed: IndexedByItem::default(),
+        }
+    }
+}
+
+impl<T> CountingStash<T> {
+    /// Returns a shared reference to the underlying [`MultiStash`].
+    pub fn as_stash(&self) -> &MultiStash<T> {
+        self.indexed.as_stash()
+    }
+
+    /// Returns the number of distinct items in the [`CountingStash`].
+    pub fn len(&self) -> usize {
+        self.indexed.len()
+    }
+
+    /// Returns the total number of items in the [`CountingStash`].
+    pub fn len_items(&self) -> usize {
+        self.indexed.len_items()
+    }
+
+    /// Returns `true` if the [`CountingStash`] contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.indexed.is_empty()
+    }
+
+    /// Returns a reference to an item at the `key` and its count if any.
+    pub fn get(&self, key: Key) -> Option<(usize, &T)> {
+        self.indexed.get(key)
+    }
+
+    /// Returns an iterator over the distinct items of the [`CountingStash`].
+    ///
+    /// The iterator yields all items, their keys and counts from start to end.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.indexed.iter()
+    }
+}
+
+impl<T> CountingStash<T>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Creates a new, empty [`CountingStash`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the [`Key`] of the item equal to `item` if any.
+    pub fn key_of(&self, item: &T) -> Option<Key> {
+        self.indexed.key_of(item)
+    }
+
+    /// Returns the number of occurrences of `item`.
+    pub fn count_of(&self, item: &T) -> usize {
+        self.key_of(item)
+            .and_then(|key| self.get(key))
+            .map_or(0, |(count, _)| count)
+    }
+
+    /// Adds a single occurrence of `item` and returns its [`Key`].
+    ///
+    /// Bumps the count of an equal item if any or inserts `item` with a count of 1.
+    ///
+    /// # Panics
+    ///
+    /// See [`IndexedByItem::put`].
+    pub fn add(&mut self, item: T) -> Key {
+        self.indexed.put(NonZeroUsize::MIN, item)
+    }
+
+    /// Removes a single occurrence of `item` and returns its remaining count.
+    ///
+    /// The item is removed entirely once its count drops to zero.
+    /// Returns `None` if no item equal to `item` is stored.
+    pub fn remove(&mut self, item: &T) -> Option<usize> {
+        let key = self.key_of(item)?;
+        self.indexed.take_one(key).map(|(remaining, _)| remaining)
+    }
+}
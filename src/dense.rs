@@ -0,0 +1,145 @@
+use super::{Key, MultiStash};
+use alloc::vec::Vec;
+use core::num::NonZeroUsize;
+
+/// A [`MultiStash`] that additionally tracks a dense ordering of its elements.
+///
+/// Every element has a stable [`Key`] as well as a dense position in `0..len`.
+/// Removals swap the last dense position into the vacated one, analogous to
+/// [`Vec::swap_remove`], so that callers can mirror them in a parallel dense array.
+#[derive(Debug, Clone)]
+pub struct DenseMultiStash<T> {
+    /// The elements and their dense positions.
+    stash: MultiStash<(usize, T)>,
+    /// The keys of all elements in dense order.
+    keys: Vec<Key>,
+}
+
+/// Describes how the dense positions changed upon a removal from a [`DenseMultiStash`].
+///
+/// This is created by [`DenseMultiStash::take_all_dense`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DenseSwap {
+    /// The dense position of the removed element.
+    removed: usize,
+    /// The dense position of the element that moved into `removed` if any.
+    moved_from: Option<usize>,
+}
+
+impl DenseSwap {
+    /// Returns the dense position of the removed element.
+    pub fn removed(&self) -> usize {
+        self.removed
+    }
+
+    /// Returns the former dense position of the element that moved into [`DenseSwap::removed`].
+    ///
+    /// Returns `None` if the removed element was the last in dense order.
+    pub fn moved_from(&self) -> Option<usize> {
+        self.moved_from
+    }
+}
+
+impl<T> Default for DenseMultiStash<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> DenseMultiStash<T> {
+    /// Creates a new, empty [`DenseMultiStash`].
+    pub const fn new() -> Self {
+        Self {
+            stash: MultiStash::new(),
+            keys: Vec::new(),
+        }
+    }
+
+    /// Returns the number of elements in the [`DenseMultiStash`].
+    pub fn len(&self) -> usize {
+        self.stash.len()
+    }
+
+    /// Returns the number of items in the [`DenseMultiStash`].
+    pub fn len_items(&self) -> usize {
+        self.stash.len_items()
+    }
+
+    /// Returns `true` if the [`DenseMultiStash`] contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.stash.is_empty()
+    }
+
+    /// Returns the keys of all elements in dense order.
+    pub fn dense_keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    /// Returns the dense position of the element at `key` if any.
+    pub fn dense_position(&self, key: Key) -> Option<usize> {
+        self.stash.get(key).map(|(_, (position, _))| *position)
+    }
+
+    /// Returns a reference to an element at the `key` if any.
+    pub fn get(&self, key: Key) -> Option<(usize, &T)> {
+        self.stash
+            .get(key)
+            .map(|(remaining, (_, item))| (remaining, item))
+    }
+
+    /// Returns a mutable reference to an element at the `key` if any.
+    pub fn get_mut(&mut self, key: Key) -> Option<(usize, &mut T)> {
+        self.stash
+            .get_mut(key)
+            .map(|(remaining, (_, item))| (remaining, item))
+    }
+
+    /// Puts an `amount` of `item` into the [`DenseMultiStash`].
+    ///
+    /// The element is appended to the end of the dense order.
+    ///
+    /// # Panics
+    ///
+    /// See [`MultiStash::put`].
+    pub fn put(&mut self, amount: NonZeroUsize, item: T) -> Key {
+        self.keys.reserve(1);
+        let key = self.stash.put(amount, (self.keys.len(), item));
+        self.keys.push(key);
+        key
+    }
+
+    /// Bumps the amount of items of the element at `key` if any.
+    ///
+    /// See [`MultiStash::bump`] for more information.
+    pub fn bump(&mut self, key: Key, amount: usize) -> Option<usize> {
+        self.stash.bump(key, amount)
+    }
+
+    /// Removes and returns the `element` at `key` and its amount of remaining items.
+    ///
+    /// The returned [`DenseSwap`] describes how the dense order changed.
+    /// Returns `None` if `key` refers to a vacant entry or is out of bounds.
+    pub fn take_all_dense(&mut self, key: Key) -> Option<(usize, T, DenseSwap)> {
+        let (remaining, (removed, item)) = self.stash.take_all(key)?;
+        self.keys.swap_remove(removed);
+        let moved_from = match self.keys.get(removed) {
+            Some(&moved) => {
+                let (_, (position, _)) = self
+                    .stash
+                    .get_mut(moved)
+                    .expect("dense keys refer to occupied entries");
+                *position = removed;
+                Some(self.keys.len())
+            }
+            None => None,
+        };
+        Some((
+            remaining,
+            item,
+            DenseSwap {
+                removed,
+                moved_from,
+            },
+        ))
+    }
+}
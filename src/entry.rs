@@ -1,11 +1,34 @@
+use core::mem;
 use core::num::NonZeroUsize;
 
+/// A single vacant or occupied entry of a [`MultiStash`].
+///
+/// [`MultiStash`]: crate::MultiStash
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Entry<T> {
+    /// A vacant entry that is linked into the free list.
     Vacant(VacantEntry),
+    /// An occupied entry storing an element and its remaining items.
     Occupied(OccupiedEntry<T>),
 }
 
+impl<T> Entry<T> {
+    /// Returns `true` if [`Entry`] is as small as [`OccupiedEntry`] for `T`.
+    ///
+    /// This is the case if the niche of [`OccupiedEntry::remaining`] is used to
+    /// discriminate between vacant and occupied entries, for example for `u32`.
+    ///
+    /// # Note
+    ///
+    /// The layout of [`Entry`] is not guaranteed by the Rust compiler and might change.
+    /// Neither [`Entry`] nor the [`MultiStash`] ever expose uninitialized memory.
+    ///
+    /// [`MultiStash`]: crate::MultiStash
+    pub const fn is_niche_optimized() -> bool {
+        mem::size_of::<Self>() == mem::size_of::<OccupiedEntry<T>>()
+    }
+}
+
 impl<T> From<VacantEntry> for Entry<T> {
     fn from(entry: VacantEntry) -> Self {
         Self::Vacant(entry)
@@ -18,24 +41,37 @@ impl<T> From<OccupiedEntry<T>> for Entry<T> {
     }
 }
 
+/// A vacant entry of a [`MultiStash`].
+///
+/// [`MultiStash`]: crate::MultiStash
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct VacantEntry {
+    /// The index of the next vacant entry in the free list.
+    ///
+    /// The free list ends with an index that is out of bounds of the entries.
     pub next_free: usize,
 }
 
 impl VacantEntry {
+    /// Creates a new [`VacantEntry`] linking to the `next_free` vacant entry.
     pub fn new(next_free: usize) -> Self {
         Self { next_free }
     }
 }
 
+/// An occupied entry of a [`MultiStash`].
+///
+/// [`MultiStash`]: crate::MultiStash
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct OccupiedEntry<T> {
+    /// The amount of remaining items of the element.
     pub remaining: NonZeroUsize,
+    /// The stored element.
     pub item: T,
 }
 
 impl<T> OccupiedEntry<T> {
+    /// Creates a new [`OccupiedEntry`] storing an `amount` of `item`.
     pub fn new(item: T, amount: NonZeroUsize) -> Self {
         Self {
             remaining: amount,
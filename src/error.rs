@@ -0,0 +1,85 @@
+use super::Key;
+use core::fmt;
+use core::num::NonZeroUsize;
+
+/// Error returned when the internal counters of a [`MultiStash`] are corrupted.
+///
+/// This can only happen if the invariants of the [`MultiStash`] have been violated.
+///
+/// [`MultiStash`]: crate::MultiStash
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Corruption {
+    /// The key of the element that was about to be taken.
+    key: Key,
+}
+
+impl Corruption {
+    /// Creates a new [`Corruption`] error for the element at `key`.
+    pub(crate) fn new(key: Key) -> Self {
+        Self { key }
+    }
+
+    /// Returns the [`Key`] of the element that was about to be taken.
+    pub fn key(&self) -> Key {
+        self.key
+    }
+}
+
+impl fmt::Display for Corruption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "counters of MultiStash would underflow when taking element at {}",
+            self.key.0
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Corruption {}
+
+/// Error returned when an element of a [`MultiStash`] would exceed its maximum amount of items.
+///
+/// See [`MultiStashBuilder::with_max_per_entry`] for more information.
+///
+/// [`MultiStash`]: crate::MultiStash
+/// [`MultiStashBuilder::with_max_per_entry`]: crate::MultiStashBuilder::with_max_per_entry
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MaxPerEntryExceeded {
+    /// The amount of items the element would have held.
+    amount: usize,
+    /// The maximum amount of items per element.
+    max: NonZeroUsize,
+}
+
+impl MaxPerEntryExceeded {
+    /// Creates a new [`MaxPerEntryExceeded`] error.
+    pub(crate) fn new(amount: usize, max: NonZeroUsize) -> Self {
+        Self { amount, max }
+    }
+
+    /// Returns the amount of items the element would have held.
+    ///
+    /// This saturates at `usize::MAX`.
+    pub fn amount(&self) -> usize {
+        self.amount
+    }
+
+    /// Returns the maximum amount of items per element.
+    pub fn max(&self) -> NonZeroUsize {
+        self.max
+    }
+}
+
+impl fmt::Display for MaxPerEntryExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "amount of {} exceeds the maximum of {} items per MultiStash element",
+            self.amount, self.max
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MaxPerEntryExceeded {}
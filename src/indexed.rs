@@ -0,0 +1,170 @@
+use super::{Iter, Key, MultiStash};
+use core::hash::Hash;
+use core::num::NonZeroUsize;
+#[cfg(feature = "hashbrown")]
+use hashbrown::HashMap;
+#[cfg(not(feature = "hashbrown"))]
+use std::collections::HashMap;
+
+/// A [`MultiStash`] that additionally allows to look up the [`Key`] of its elements.
+///
+/// Equal elements share a single entry so that every element has a unique [`Key`].
+/// The base [`MultiStash`] remains index-only which is why this is an opt-in wrapper.
+///
+/// The side map is a [`hashbrown`] map if the `hashbrown` feature is enabled
+/// which allows to use this without the `std` feature.
+///
+/// [`hashbrown`]: https://docs.rs/hashbrown
+#[derive(Debug)]
+pub struct IndexedByItem<T> {
+    /// The underlying [`MultiStash`] storing the elements.
+    stash: MultiStash<T>,
+    /// Maps every stored element to its [`Key`] in `stash`.
+    keys: HashMap<T, Key>,
+}
+
+impl<T: Clone> Clone for IndexedByItem<T> {
+    fn clone(&self) -> Self {
+        Self {
+            stash: self.stash.clone(),
+            keys: self.keys.clone(),
+        }
+    }
+
+    /// Reuses the allocations of both the stash and the side map of `self`.
+    fn clone_from(&mut self, source: &Self) {
+        self.stash.clone_from(&source.stash);
+        self.keys.clone_from(&source.keys);
+    }
+}
+
+impl<T> Default for IndexedByItem<T> {
+    fn default() -> Self {
+        Self {
+            stash: MultiStash::new(),
+            keys: HashMap::new(),
+        }
+    }
+}
+
+impl<T> IndexedByItem<T> {
+    /// Returns a shared reference to the underlying [`MultiStash`].
+    pub fn as_stash(&self) -> &MultiStash<T> {
+        &self.stash
+    }
+
+    /// Returns the number of elements in the [`IndexedByItem`].
+    pub fn len(&self) -> usize {
+        self.stash.len()
+    }
+
+    /// Returns the number of items in the [`IndexedByItem`].
+    pub fn len_items(&self) -> usize {
+        self.stash.len_items()
+    }
+
+    /// Returns `true` if the [`IndexedByItem`] contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.stash.is_empty()
+    }
+
+    /// Returns a reference to an element at the `key` if any.
+    pub fn get(&self, key: Key) -> Option<(usize, &T)> {
+        self.stash.get(key)
+    }
+
+    /// Bumps the amount of items of the element at `key` if any.
+    ///
+    /// See [`MultiStash::bump`] for more information.
+    pub fn bump(&mut self, key: Key, amount: usize) -> Option<usize> {
+        self.stash.bump(key, amount)
+    }
+
+    /// Returns an iterator over the elements of the [`IndexedByItem`].
+    ///
+    /// The iterator yields all elements, their keys and remaining items from start to end.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.stash.iter()
+    }
+}
+
+impl<T> IndexedByItem<T>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Creates a new, empty [`IndexedByItem`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the [`Key`] of the element equal to `item` if any.
+    ///
+    /// This is O(1) on average.
+    pub fn key_of(&self, item: &T) -> Option<Key> {
+        self.keys.get(item).copied()
+    }
+
+    /// Puts an `amount` of `item` into the [`IndexedByItem`].
+    ///
+    /// If an equal element is already stored its amount is bumped by `amount` instead
+    /// and its [`Key`] is returned.
+    ///
+    /// # Panics
+    ///
+    /// See [`MultiStash::put`] and [`MultiStash::bump`].
+    pub fn put(&mut self, amount: NonZeroUsize, item: T) -> Key {
+        if let Some(key) = self.key_of(&item) {
+            self.stash.bump(key, amount.get());
+            return key;
+        }
+        let key = self.stash.put(amount, item.clone());
+        self.keys.insert(item, key);
+        key
+    }
+
+    /// Removes and returns the `element` at `key` and its amount of remaining items.
+    ///
+    /// Returns `None` if `key` refers to a vacant entry or is out of bounds.
+    pub fn take_all(&mut self, key: Key) -> Option<(usize, T)> {
+        let (remaining, item) = self.stash.take_all(key)?;
+        self.keys.remove(&item);
+        Some((remaining, item))
+    }
+
+    /// Returns a single item of the `element` at `key`
+    /// and the amount of remaining items after this operation.
+    ///
+    /// Remove the `element` if no items are left after this operation.
+    /// Returns `None` if `key` refers to a vacant entry or is out of bounds.
+    pub fn take_one(&mut self, key: Key) -> Option<(usize, T)> {
+        let (remaining, item) = self.stash.take_one(key)?;
+        if remaining == 0 {
+            self.keys.remove(&item);
+        }
+        Some((remaining, item))
+    }
+
+    /// Replaces the `element` at `key` with `item` and returns the old `element`.
+    ///
+    /// The amount of remaining items is unchanged.
+    /// Returns `None` and drops `item` if `key` refers to a vacant entry or is out of bounds.
+    ///
+    /// # Panics
+    ///
+    /// If an element equal to `item` is stored at another [`Key`].
+    pub fn replace(&mut self, key: Key, item: T) -> Option<T> {
+        if let Some(other) = self.key_of(&item) {
+            if other != key {
+                panic!(
+                    "cannot replace element at {} with an element that is already stored at {}",
+                    key.0, other.0,
+                )
+            }
+        }
+        let (_, old) = self.stash.get_mut(key)?;
+        let old = core::mem::replace(old, item.clone());
+        self.keys.remove(&old);
+        self.keys.insert(item, key);
+        Some(old)
+    }
+}
@@ -1,12 +1,24 @@
 use super::{Entry, Key, MultiStash};
 use alloc::vec;
+use core::fmt;
 use core::iter::{Enumerate, FusedIterator};
 use core::slice;
 
+/// Decrements the `remaining` count of occupied entries of an iterator.
+///
+/// Saturates at zero in release builds so that an inconsistent [`MultiStash`]
+/// cannot wrap the iterator length around.
+fn dec_remaining(remaining: &mut usize) {
+    debug_assert!(
+        *remaining > 0,
+        "occupied entry found after remaining hit zero"
+    );
+    *remaining = remaining.saturating_sub(1);
+}
+
 /// Immutable [`MultiStash`] iterator.
 ///
 /// This struct is created by [`MultiStash::iter`].
-#[derive(Debug)]
 pub struct Iter<'a, T> {
     /// The amount of remaining `Entry::Occupied` entries.
     remaining: usize,
@@ -33,12 +45,24 @@ impl<'a, T> Iterator for Iter<'a, T> {
                 None => return None,
                 Some((_, Entry::Vacant(_))) => continue,
                 Some((index, Entry::Occupied(entry))) => {
-                    self.remaining -= 1;
+                    dec_remaining(&mut self.remaining);
                     return Some((Key(index), entry.remaining.get(), &entry.item));
                 }
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+
+    fn count(self) -> usize {
+        self.remaining
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
 }
 
 impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
@@ -48,7 +72,7 @@ impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
                 None => return None,
                 Some((_, Entry::Vacant(_))) => continue,
                 Some((index, Entry::Occupied(entry))) => {
-                    self.remaining -= 1;
+                    dec_remaining(&mut self.remaining);
                     return Some((Key(index), entry.remaining.get(), &entry.item));
                 }
             }
@@ -64,10 +88,27 @@ impl<'a, T> ExactSizeIterator for Iter<'a, T> {
 
 impl<'a, T> FusedIterator for Iter<'a, T> {}
 
+impl<'a, T> fmt::Debug for Iter<'a, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            remaining: self.remaining,
+            iter: self.iter.clone(),
+        }
+    }
+}
+
 /// Mutable [`MultiStash`] iterator.
 ///
 /// This struct is created by [`MultiStash::iter_mut`].
-#[derive(Debug)]
 pub struct IterMut<'a, T> {
     /// The amount of remaining `Entry::Occupied` entries.
     remaining: usize,
@@ -94,12 +135,24 @@ impl<'a, T> Iterator for IterMut<'a, T> {
                 None => return None,
                 Some((_, Entry::Vacant(_))) => continue,
                 Some((index, Entry::Occupied(entry))) => {
-                    self.remaining -= 1;
+                    dec_remaining(&mut self.remaining);
                     return Some((Key(index), entry.remaining.get(), &mut entry.item));
                 }
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+
+    fn count(self) -> usize {
+        self.remaining
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
 }
 
 impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
@@ -109,7 +162,7 @@ impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
                 None => return None,
                 Some((_, Entry::Vacant(_))) => continue,
                 Some((index, Entry::Occupied(entry))) => {
-                    self.remaining -= 1;
+                    dec_remaining(&mut self.remaining);
                     return Some((Key(index), entry.remaining.get(), &mut entry.item));
                 }
             }
@@ -125,11 +178,18 @@ impl<'a, T> ExactSizeIterator for IterMut<'a, T> {
 
 impl<'a, T> FusedIterator for IterMut<'a, T> {}
 
+impl<'a, T> fmt::Debug for IterMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IterMut")
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
+
 /// An iterator that moves out of a [`MultiStash`].
 ///
 /// This `struct` is created by the `into_iter` method on [`MultiStash`]
 /// (provided by the [`IntoIterator`] trait).
-#[derive(Debug)]
 pub struct IntoIter<T> {
     /// The amount of remaining `Entry::Occupied` entries.
     remaining: usize,
@@ -156,12 +216,24 @@ impl<T> Iterator for IntoIter<T> {
                 None => return None,
                 Some((_, Entry::Vacant(_))) => continue,
                 Some((index, Entry::Occupied(entry))) => {
-                    self.remaining -= 1;
+                    dec_remaining(&mut self.remaining);
                     return Some((Key(index), entry.remaining.get(), entry.item));
                 }
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+
+    fn count(self) -> usize {
+        self.remaining
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
 }
 
 impl<T> DoubleEndedIterator for IntoIter<T> {
@@ -171,7 +243,7 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
                 None => return None,
                 Some((_, Entry::Vacant(_))) => continue,
                 Some((index, Entry::Occupied(entry))) => {
-                    self.remaining -= 1;
+                    dec_remaining(&mut self.remaining);
                     return Some((Key(index), entry.remaining.get(), entry.item));
                 }
             }
@@ -186,3 +258,11 @@ impl<T> ExactSizeIterator for IntoIter<T> {
 }
 
 impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> fmt::Debug for IntoIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IntoIter")
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
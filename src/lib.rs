@@ -1,16 +1,44 @@
 #![no_std]
 
+mod builder;
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+mod counting;
+mod dense;
 mod entry;
+mod error;
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+mod indexed;
 mod iter;
+mod meta;
+mod split;
+#[cfg(feature = "stats")]
+mod stats;
 
 #[cfg(test)]
 mod tests;
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
-use self::entry::{Entry, OccupiedEntry, VacantEntry};
+pub use self::builder::MultiStashBuilder;
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+pub use self::counting::CountingStash;
+pub use self::dense::{DenseMultiStash, DenseSwap};
+pub use self::entry::{Entry, OccupiedEntry, VacantEntry};
+pub use self::error::{Corruption, MaxPerEntryExceeded};
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+pub use self::indexed::IndexedByItem;
 pub use self::iter::{IntoIter, Iter, IterMut};
+pub use self::meta::{ItemMut, ItemRef, OccupiedHandle, RemainingMut, SlotMeta, TakeResult};
+pub use self::split::SubStashMut;
+#[cfg(feature = "stats")]
+pub use self::stats::Stats;
+use alloc::vec;
 use alloc::vec::Vec;
+use core::cell::Cell;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
 use core::mem;
 use core::num::NonZeroUsize;
 use core::ops::{Index, IndexMut};
@@ -24,9 +52,14 @@ use core::ops::{Index, IndexMut};
 /// - [`MultiStash::take_all`]
 /// - [`MultiStash::get`]
 /// - [`MultiStash::get_mut`]
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug)]
 pub struct MultiStash<T> {
     /// The next vacant or free slot to allocate.
+    ///
+    /// # Note
+    ///
+    /// If this is out of bounds of `entries` the free list is empty
+    /// and new elements are pushed to the end of `entries`.
     free: usize,
     /// The number of items stored in the [`MultiStash`].
     ///
@@ -40,6 +73,17 @@ pub struct MultiStash<T> {
     ///
     /// Each [`Entry::Occupied`] might store multiple items.
     len_occupied: usize,
+    /// The index of the first occupied entry if any.
+    min_occupied: Option<usize>,
+    /// The index of the last occupied entry if any.
+    max_occupied: Option<usize>,
+    /// The maximum amount of items a single element may hold if any.
+    max_per_entry: Option<NonZeroUsize>,
+    /// The number of entries to grow by when full if any.
+    growth_increment: Option<NonZeroUsize>,
+    /// Cumulative statistics about the operations performed on the [`MultiStash`].
+    #[cfg(feature = "stats")]
+    stats: Stats,
     /// The entries of the [`MultiStash`].
     entries: Vec<Entry<T>>,
 }
@@ -62,6 +106,163 @@ impl From<Key> for usize {
     }
 }
 
+impl<T> MultiStash<T> {
+    /// Returns all fields of the [`MultiStash`] that take part in comparisons and hashing.
+    ///
+    /// # Note
+    ///
+    /// The statistics are diagnostics only and thus excluded.
+    #[allow(clippy::type_complexity)]
+    fn cmp_fields(
+        &self,
+    ) -> (
+        usize,
+        usize,
+        usize,
+        Option<usize>,
+        Option<usize>,
+        Option<NonZeroUsize>,
+        Option<NonZeroUsize>,
+        &[Entry<T>],
+    ) {
+        (
+            self.free,
+            self.len_items,
+            self.len_occupied,
+            self.min_occupied,
+            self.max_occupied,
+            self.max_per_entry,
+            self.growth_increment,
+            &self.entries,
+        )
+    }
+}
+
+/// Checks that `amount` does not exceed the maximum amount of items per element.
+pub(crate) fn check_max_per_entry(
+    max_per_entry: Option<NonZeroUsize>,
+    amount: usize,
+) -> Result<(), MaxPerEntryExceeded> {
+    match max_per_entry {
+        Some(max) if amount > max.get() => Err(MaxPerEntryExceeded::new(amount, max)),
+        _ => Ok(()),
+    }
+}
+
+/// Ensures that `amount` does not exceed the maximum amount of items per element.
+///
+/// # Panics
+///
+/// If `amount` exceeds the maximum amount of items per element.
+pub(crate) fn ensure_max_per_entry(max_per_entry: Option<NonZeroUsize>, amount: usize) {
+    if let Err(error) = check_max_per_entry(max_per_entry, amount) {
+        panic!("{error}")
+    }
+}
+
+/// Returns the number of items `len_items` bumped by `amount`.
+///
+/// # Panics
+///
+/// If the number of items overflows.
+pub(crate) fn checked_len_items(len_items: usize, amount: usize) -> usize {
+    len_items.checked_add(amount).unwrap_or_else(|| {
+        panic!(
+            "failed to add {} items to MultiStash of length {}",
+            amount, len_items
+        )
+    })
+}
+
+/// Restores the free list and the occupied bounds of a [`MultiStash`] when dropped.
+///
+/// Bulk operations edit the entries directly and use this to keep the [`MultiStash`]
+/// valid even if they panic midway, e.g. in a user provided closure.
+struct RestoreGuard<'a, T> {
+    /// The [`MultiStash`] whose entries are edited.
+    stash: &'a mut MultiStash<T>,
+    /// Set to `true` once the entries have been edited and require a restore.
+    dirty: bool,
+}
+
+impl<'a, T> RestoreGuard<'a, T> {
+    /// Creates a new [`RestoreGuard`] for `stash` that has not edited any entries yet.
+    fn new(stash: &'a mut MultiStash<T>) -> Self {
+        Self {
+            stash,
+            dirty: false,
+        }
+    }
+}
+
+impl<T> Drop for RestoreGuard<'_, T> {
+    fn drop(&mut self) {
+        if self.dirty {
+            self.stash.rebuild_free_list();
+            self.stash.recompute_occupied_bounds();
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for MultiStash<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp_fields() == other.cmp_fields()
+    }
+}
+
+impl<T: Eq> Eq for MultiStash<T> {}
+
+impl<T: PartialOrd> PartialOrd for MultiStash<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.cmp_fields().partial_cmp(&other.cmp_fields())
+    }
+}
+
+impl<T: Ord> Ord for MultiStash<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_fields().cmp(&other.cmp_fields())
+    }
+}
+
+impl<T: Hash> Hash for MultiStash<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cmp_fields().hash(state)
+    }
+}
+
+impl<T: Clone> Clone for MultiStash<T> {
+    fn clone(&self) -> Self {
+        Self {
+            free: self.free,
+            len_items: self.len_items,
+            len_occupied: self.len_occupied,
+            min_occupied: self.min_occupied,
+            max_occupied: self.max_occupied,
+            max_per_entry: self.max_per_entry,
+            growth_increment: self.growth_increment,
+            #[cfg(feature = "stats")]
+            stats: self.stats,
+            entries: self.entries.clone(),
+        }
+    }
+
+    /// Reuses the allocated entries of `self` where possible.
+    fn clone_from(&mut self, source: &Self) {
+        self.free = source.free;
+        self.len_items = source.len_items;
+        self.len_occupied = source.len_occupied;
+        self.min_occupied = source.min_occupied;
+        self.max_occupied = source.max_occupied;
+        self.max_per_entry = source.max_per_entry;
+        self.growth_increment = source.growth_increment;
+        #[cfg(feature = "stats")]
+        {
+            self.stats = source.stats;
+        }
+        self.entries.clone_from(&source.entries);
+    }
+}
+
 impl<T> Default for MultiStash<T> {
     fn default() -> Self {
         Self::new()
@@ -72,11 +273,17 @@ impl<T> MultiStash<T> {
     /// Construct a new, empty [`MultiStash`].
     ///
     /// The [`MultiStash`] will not allocate until items are put into it.
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Self {
             free: 0,
             len_items: 0,
             len_occupied: 0,
+            min_occupied: None,
+            max_occupied: None,
+            max_per_entry: None,
+            growth_increment: None,
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
             entries: Vec::new(),
         }
     }
@@ -105,26 +312,209 @@ impl<T> MultiStash<T> {
             free: 0,
             len_items: 0,
             len_occupied: 0,
+            min_occupied: None,
+            max_occupied: None,
+            max_per_entry: None,
+            growth_increment: None,
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
             entries: Vec::with_capacity(capacity),
         }
     }
 
+    /// Constructs a new [`MultiStash`] from `iter` with its elements in reverse order.
+    ///
+    /// The last element yielded by `iter` is stored at `Key(0)` and the first at the
+    /// highest key, so that the most recently inserted element is addressable at the top.
+    ///
+    /// # Note
+    ///
+    /// The entries are collected and then reversed in place without reallocating.
+    ///
+    /// # Panics
+    ///
+    /// - If the number of items in the [`MultiStash`] overflows.
+    /// - If the new capacity exceeds `isize::MAX` bytes.
+    pub fn from_iter_rev<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (NonZeroUsize, T)>,
+    {
+        let mut stash = Self::new();
+        let mut len_items = 0;
+        stash.entries = iter
+            .into_iter()
+            .map(|(amount, item)| {
+                len_items = checked_len_items(len_items, amount.get());
+                Entry::from(OccupiedEntry::new(item, amount))
+            })
+            .collect();
+        stash.entries.reverse();
+        stash.free = stash.len_entries();
+        stash.len_items = len_items;
+        stash.len_occupied = stash.len_entries();
+        stash.min_occupied = (stash.len_occupied > 0).then_some(0);
+        stash.max_occupied = stash.len_occupied.checked_sub(1);
+        #[cfg(feature = "stats")]
+        for _ in 0..stash.len_occupied {
+            stash.stats.record_put(false);
+        }
+        stash
+    }
+
+    /// Consumes the [`MultiStash`] and returns its raw parts.
+    ///
+    /// Returns the entries, the head of the free list, the number of items
+    /// and the number of occupied entries in this order.
+    ///
+    /// # Note
+    ///
+    /// The configuration such as [`MultiStash::max_per_entry`] is not part of the raw parts.
+    pub fn into_parts(self) -> (Vec<Entry<T>>, usize, usize, usize) {
+        (self.entries, self.free, self.len_items, self.len_occupied)
+    }
+
+    /// Constructs a new [`MultiStash`] from raw parts as returned by [`MultiStash::into_parts`].
+    ///
+    /// # Note
+    ///
+    /// This scans `entries` for the first and last occupied entry.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the raw parts uphold the invariants of the [`MultiStash`]:
+    ///
+    /// - `free` is the head of a free list that links all vacant entries of `entries` and
+    ///   ends with an index that is out of bounds of `entries`.
+    /// - `len_items` is the sum of the remaining items of all occupied entries.
+    /// - `len_occupied` is the number of occupied entries.
+    ///
+    /// Otherwise the [`MultiStash`] is inconsistent: methods such as [`MultiStash::put`]
+    /// may panic, [`MultiStash::len`] and [`MultiStash::len_items`] report wrong counts
+    /// and iterators report wrong lengths.
+    /// Invariants that are not upheld may be restored via [`MultiStash::recompute_counters`]
+    /// and [`MultiStash::rebuild_free_list`] before any other method is called.
+    pub unsafe fn from_parts_unchecked(
+        entries: Vec<Entry<T>>,
+        free: usize,
+        len_items: usize,
+        len_occupied: usize,
+    ) -> Self {
+        let mut stash = Self::new();
+        stash.entries = entries;
+        stash.free = free;
+        stash.len_items = len_items;
+        stash.len_occupied = len_occupied;
+        stash.recompute_occupied_bounds();
+        stash
+    }
+
+    /// Returns a mutable slice over the raw entries of the [`MultiStash`].
+    ///
+    /// This allows bulk edits of the entries that the safe API cannot express efficiently,
+    /// for example turning occupied entries vacant or changing their remaining items.
+    ///
+    /// # Note
+    ///
+    /// Use [`MultiStash::check_invariants`] to verify that all invariants have been restored.
+    ///
+    /// # Safety
+    ///
+    /// The entries and the counters of the [`MultiStash`] must stay consistent, so before
+    /// calling any other method the caller must restore all invariants affected by the edits:
+    ///
+    /// - The free list links all vacant entries and ends with an out of bounds index.
+    /// - The number of items is the sum of the remaining items of all occupied entries.
+    /// - The number of occupied entries as well as the first and last occupied entries match.
+    /// - No element exceeds the [`MultiStash::max_per_entry`].
+    ///
+    /// Otherwise later calls such as [`MultiStash::put`] may panic and methods such as
+    /// [`MultiStash::len`] and [`MultiStash::len_items`] report wrong counts.
+    /// Use [`MultiStash::recompute_counters`] and [`MultiStash::rebuild_free_list`]
+    /// to restore the counters and the free list.
+    #[cfg(feature = "raw")]
+    pub unsafe fn entries_mut(&mut self) -> &mut [Entry<T>] {
+        &mut self.entries
+    }
+
+    /// Returns `true` if all invariants of the [`MultiStash`] hold.
+    ///
+    /// This checks the counters, the first and last occupied entries,
+    /// the [`MultiStash::max_per_entry`] and the free list.
+    ///
+    /// # Note
+    ///
+    /// This is O(n) and mainly intended to validate raw edits.
+    pub fn check_invariants(&self) -> bool {
+        let mut len_items = 0_usize;
+        let mut len_occupied = 0;
+        for entry in &self.entries {
+            if let Entry::Occupied(entry) = entry {
+                let remaining = entry.remaining.get();
+                if self.max_per_entry.is_some_and(|max| remaining > max.get()) {
+                    return false;
+                }
+                let Some(new_len_items) = len_items.checked_add(remaining) else {
+                    return false;
+                };
+                len_items = new_len_items;
+                len_occupied += 1;
+            }
+        }
+        let is_occupied = |entry: &Entry<T>| matches!(entry, Entry::Occupied(_));
+        if len_items != self.len_items
+            || len_occupied != self.len_occupied
+            || self.entries.iter().position(is_occupied) != self.min_occupied
+            || self.entries.iter().rposition(is_occupied) != self.max_occupied
+        {
+            return false;
+        }
+        let mut len_free = 0;
+        let mut current = self.free;
+        while current < self.len_entries() {
+            let Entry::Vacant(entry) = &self.entries[current] else {
+                return false;
+            };
+            len_free += 1;
+            if len_free > self.vacant_count() {
+                // The free list contains a cycle.
+                return false;
+            }
+            current = entry.next_free;
+        }
+        len_free == self.vacant_count()
+    }
+
     /// Returns the total number of elements the [`MultiStash`] can hold without reallocating.
     pub fn capacity(&self) -> usize {
         self.entries.capacity()
     }
 
+    /// Returns `true` if the [`MultiStash`] has allocated memory for its entries.
+    pub fn has_allocated(&self) -> bool {
+        self.capacity() > 0
+    }
+
     /// Reserves capacity for at least `additional` more elements to be inserted
     /// in the given [`MultiStash`]. The collection may reserve more space to
     /// speculatively avoid frequent reallocations. After calling `reserve`,
     /// capacity will be greater than or equal to `self.len() + additional`.
     /// Does nothing if capacity is already sufficient.
     ///
+    /// # Note
+    ///
+    /// Vacant entries are reused by future insertions and thus only
+    /// `additional` minus the number of vacant entries are reserved.
+    ///
     /// # Panics
     ///
     /// Panics if the new capacity exceeds `isize::MAX` bytes.
     pub fn reserve(&mut self, additional: usize) {
-        self.entries.reserve(additional);
+        #[cfg(feature = "stats")]
+        let capacity = self.capacity();
+        self.entries
+            .reserve(additional.saturating_sub(self.vacant_count()));
+        #[cfg(feature = "stats")]
+        self.stats.record_capacity(capacity, self.capacity());
     }
 
     /// Reserves the minimum capacity for at least `additional` more elements to
@@ -138,13 +528,105 @@ impl<T> MultiStash<T> {
     /// requests. Therefore, capacity can not be relied upon to be precisely
     /// minimal. Prefer [`reserve`] if future insertions are expected.
     ///
+    /// Vacant entries are reused by future insertions and thus only
+    /// `additional` minus the number of vacant entries are reserved.
+    ///
     /// [`reserve`]: MultiStash::reserve
     ///
     /// # Panics
     ///
     /// Panics if the new capacity exceeds `isize::MAX` bytes.
     pub fn reserve_exact(&mut self, additional: usize) {
-        self.entries.reserve_exact(additional);
+        #[cfg(feature = "stats")]
+        let capacity = self.capacity();
+        self.entries
+            .reserve_exact(additional.saturating_sub(self.vacant_count()));
+        #[cfg(feature = "stats")]
+        self.stats.record_capacity(capacity, self.capacity());
+    }
+
+    /// Reserves capacity for `additional` new entries at the end of the [`MultiStash`].
+    ///
+    /// With a [`MultiStash::growth_increment`] the capacity grows by the smallest multiple
+    /// of the increment that fits, otherwise it grows geometrically like a [`Vec`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    fn reserve_entries(&mut self, additional: usize) {
+        match self.growth_increment {
+            Some(increment) => {
+                let spare = self.capacity() - self.len_entries();
+                let missing = additional.saturating_sub(spare);
+                if missing > 0 {
+                    let grow_by = missing.div_ceil(increment.get()) * increment.get();
+                    self.entries.reserve_exact(spare + grow_by);
+                }
+            }
+            None => self.entries.reserve(additional),
+        }
+    }
+
+    /// Shrinks the capacity of the [`MultiStash`] as much as possible.
+    ///
+    /// Vacant entries at the end of the [`MultiStash`] are removed first
+    /// as if by [`MultiStash::truncate_vacant_tail`].
+    ///
+    /// # Note
+    ///
+    /// Use [`MultiStash::exact_capacity`] to query whether the allocator trimmed the capacity fully.
+    pub fn shrink_to_fit(&mut self) {
+        #[cfg(feature = "stats")]
+        let capacity = self.capacity();
+        self.truncate_vacant_tail();
+        self.entries.shrink_to_fit();
+        #[cfg(feature = "stats")]
+        self.stats.record_capacity(capacity, self.capacity());
+    }
+
+    /// Shrinks the capacity of the [`MultiStash`] as much as possible and returns the reclaimed bytes.
+    ///
+    /// See [`MultiStash::shrink_to_fit`] for more information.
+    ///
+    /// # Note
+    ///
+    /// The reclaimed bytes are computed from the change in capacity
+    /// and the size of a single [`Entry`].
+    pub fn shrink_to_fit_reporting(&mut self) -> usize {
+        let capacity = self.capacity();
+        self.shrink_to_fit();
+        (capacity - self.capacity()) * mem::size_of::<Entry<T>>()
+    }
+
+    /// Returns `true` if the capacity of the [`MultiStash`] equals its number of entries.
+    ///
+    /// This is usually the case after [`MultiStash::shrink_to_fit`] unless the allocator
+    /// keeps some excess capacity. Interior vacant entries count towards the entries and
+    /// thus do not prevent an exact capacity.
+    pub fn exact_capacity(&self) -> bool {
+        self.capacity() == self.len_entries()
+    }
+
+    /// Returns the cumulative statistics about the operations performed on the [`MultiStash`].
+    ///
+    /// The statistics are kept across [`MultiStash::clear`].
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Returns the maximum amount of items a single element may hold if any.
+    ///
+    /// See [`MultiStashBuilder::with_max_per_entry`] for more information.
+    pub fn max_per_entry(&self) -> Option<NonZeroUsize> {
+        self.max_per_entry
+    }
+
+    /// Returns the number of entries the [`MultiStash`] grows by when full if any.
+    ///
+    /// See [`MultiStashBuilder::with_growth_increment`] for more information.
+    pub fn growth_increment(&self) -> Option<NonZeroUsize> {
+        self.growth_increment
     }
 
     /// Returns the number of vacant or occupied [`Entry`] in the [`MultiStash`].
@@ -152,6 +634,17 @@ impl<T> MultiStash<T> {
         self.entries.len()
     }
 
+    /// Returns the number of vacant entries in the [`MultiStash`].
+    ///
+    /// This is also the length of the free list since every vacant entry is linked into it.
+    ///
+    /// # Note
+    ///
+    /// This is O(1) and does not traverse the free list.
+    pub fn vacant_count(&self) -> usize {
+        self.len_entries() - self.len_occupied()
+    }
+
     /// Returns the number of items in the [`MultiStash`].
     ///
     /// # Note
@@ -161,6 +654,28 @@ impl<T> MultiStash<T> {
         self.len_items
     }
 
+    /// Returns the number of items of the elements with keys in `start..end`.
+    ///
+    /// The range is clamped to the entries of the [`MultiStash`].
+    /// Returns `0` if the range is empty.
+    ///
+    /// # Note
+    ///
+    /// This is O(n) in the length of the range.
+    pub fn items_in_range(&self, start: Key, end: Key) -> usize {
+        let end = end.0.min(self.len_entries());
+        let Some(entries) = self.entries.get(start.0..end) else {
+            return 0;
+        };
+        entries
+            .iter()
+            .map(|entry| match entry {
+                Entry::Occupied(entry) => entry.remaining.get(),
+                Entry::Vacant(_) => 0,
+            })
+            .sum()
+    }
+
     /// Returns the number of elements in the [`MultiStash`].
     ///
     /// # Note
@@ -184,180 +699,1379 @@ impl<T> MultiStash<T> {
         self.len_occupied() == 0
     }
 
+    /// Returns the [`Key`] of the first element of the [`MultiStash`] if any.
+    ///
+    /// # Note
+    ///
+    /// This is O(1) since the first occupied entry is tracked by all mutating operations.
+    /// Removing the first element requires a scan for the next one though.
+    pub fn min_key(&self) -> Option<Key> {
+        self.min_occupied.map(Key)
+    }
+
+    /// Returns the [`Key`] of the last element of the [`MultiStash`] if any.
+    ///
+    /// # Note
+    ///
+    /// This is O(1) since the last occupied entry is tracked by all mutating operations.
+    /// Removing the last element requires a scan for the previous one though.
+    pub fn max_key(&self) -> Option<Key> {
+        self.max_occupied.map(Key)
+    }
+
+    /// Returns the [`Key`] of the `rank`-th occupied entry in ascending key order if any.
+    ///
+    /// Returns `None` if `rank` is greater than or equal to [`MultiStash::len`].
+    ///
+    /// # Note
+    ///
+    /// This is O(n) since it scans the entries of the [`MultiStash`].
+    pub fn key_at_rank(&self, rank: usize) -> Option<Key> {
+        if rank >= self.len() {
+            return None;
+        }
+        self.iter().nth(rank).map(|(key, _, _)| key)
+    }
+
     /// Returns a reference to an element at the `key` if any.
     pub fn get(&self, key: Key) -> Option<(usize, &T)> {
+        self.occupied_entry(key)
+            .map(|entry| (entry.remaining.get(), &entry.item))
+    }
+
+    /// Returns `true` if `key` is in bounds and refers to an occupied entry.
+    ///
+    /// This is the validity check all accessors of the [`MultiStash`] agree with.
+    pub fn is_valid(&self, key: Key) -> bool {
+        self.occupied_entry(key).is_some()
+    }
+
+    /// Returns a reference to the occupied entry at `key` if any.
+    fn occupied_entry(&self, key: Key) -> Option<&OccupiedEntry<T>> {
         match self.entries.get(key.0) {
-            Some(Entry::Occupied(entry)) => Some((entry.remaining.get(), &entry.item)),
+            Some(Entry::Occupied(entry)) => Some(entry),
             _ => None,
         }
     }
 
-    /// Returns a mutable reference to an element at the `key` if any.
-    pub fn get_mut(&mut self, key: Key) -> Option<(usize, &mut T)> {
+    /// Returns a mutable reference to the occupied entry at `key` if any.
+    fn occupied_entry_mut(&mut self, key: Key) -> Option<&mut OccupiedEntry<T>> {
         match self.entries.get_mut(key.0) {
-            Some(Entry::Occupied(entry)) => Some((entry.remaining.get(), &mut entry.item)),
+            Some(Entry::Occupied(entry)) => Some(entry),
             _ => None,
         }
     }
 
+    /// Returns a mutable reference to an element at the `key` if any.
+    pub fn get_mut(&mut self, key: Key) -> Option<(usize, &mut T)> {
+        self.occupied_entry_mut(key)
+            .map(|entry| (entry.remaining.get(), &mut entry.item))
+    }
+
+    /// Returns a reference to an element at the `key` and its remaining items if any.
+    ///
+    /// Unlike [`MultiStash::get`] this returns a struct with named accessors.
+    pub fn get_ref(&self, key: Key) -> Option<ItemRef<'_, T>> {
+        self.get(key)
+            .map(|(remaining, item)| ItemRef::new(remaining, item))
+    }
+
+    /// Returns a mutable reference to an element at the `key` and its remaining items if any.
+    ///
+    /// Unlike [`MultiStash::get_mut`] this returns a struct with named accessors.
+    pub fn get_mut_ref(&mut self, key: Key) -> Option<ItemMut<'_, T>> {
+        self.get_mut(key)
+            .map(|(remaining, item)| ItemMut::new(remaining, item))
+    }
+
+    /// Returns meta information about the slot at `key` if it is in bounds.
+    ///
+    /// Unlike [`MultiStash::get`] this also returns information about vacant slots,
+    /// for example whether the slot is reused by the next [`MultiStash::put`].
+    pub fn get_with_meta(&self, key: Key) -> Option<SlotMeta<'_, T>> {
+        let is_next_free_target = key.0 == self.free;
+        match self.entries.get(key.0)? {
+            Entry::Occupied(entry) => Some(SlotMeta::new(
+                entry.remaining.get(),
+                Some(&entry.item),
+                is_next_free_target,
+            )),
+            Entry::Vacant(_) => Some(SlotMeta::new(0, None, is_next_free_target)),
+        }
+    }
+
+    /// Returns the [`Key`] at which the next [`MultiStash::put`] will insert its element.
+    ///
+    /// This is either a reused vacant slot or the new end of the [`MultiStash`].
+    ///
+    /// # Note
+    ///
+    /// The returned [`Key`] is only valid until the next mutating call on the [`MultiStash`].
+    pub fn next_free_key(&self) -> Key {
+        Key(self.free.min(self.len_entries()))
+    }
+
     /// Puts an `amount` of `item` into the [`MultiStash`].
     ///
     /// # Panics
     ///
-    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    /// - If the number of items in the [`MultiStash`] overflows.
+    /// - If the new capacity exceeds `isize::MAX` bytes.
+    /// - If `amount` exceeds the [`MultiStash::max_per_entry`].
+    ///
+    /// The [`MultiStash`] remains unchanged if this panics.
     pub fn put(&mut self, amount: NonZeroUsize, item: T) -> Key {
-        let key = Key(self.free);
-        self.free = if self.free == self.len_entries() {
+        ensure_max_per_entry(self.max_per_entry, amount.get());
+        let len_items = checked_len_items(self.len_items, amount.get());
+        let key = if self.free >= self.len_entries() {
+            let key = Key(self.len_entries());
+            #[cfg(feature = "stats")]
+            let capacity = self.capacity();
+            self.reserve_entries(1);
             self.entries
                 .push(Entry::from(OccupiedEntry::new(item, amount)));
-            self.free.checked_add(1).unwrap()
+            self.free = self.len_entries();
+            #[cfg(feature = "stats")]
+            {
+                self.stats.record_put(false);
+                self.stats.record_capacity(capacity, self.capacity());
+            }
+            key
         } else {
+            let key = Key(self.free);
+            let len_entries = self.len_entries();
             // # Safety: It is an invariant of `MultiStash` that `self.free` only ever stores
-            //           indices to populated entries in `self.items` if `self.free != self.len_entries()`.
+            //           indices to vacant entries in `self.entries` if `self.free < self.len_entries()`.
             let cell = unsafe { self.entries.get_unchecked_mut(self.free) };
-            match mem::replace(cell, Entry::from(OccupiedEntry::new(item, amount))) {
-                Entry::Vacant(entry) => entry.next_free,
+            self.free = match mem::replace(cell, Entry::from(OccupiedEntry::new(item, amount))) {
+                Entry::Vacant(entry) => entry.next_free.min(len_entries),
                 _ => unreachable!(
                     "asserted that the entry at `self.free` ({}) is vacant",
                     self.free
                 ),
-            }
+            };
+            #[cfg(feature = "stats")]
+            self.stats.record_put(true);
+            key
         };
-        self.bump_len_items(amount.get());
+        self.len_items = len_items;
         self.len_occupied += 1;
+        self.track_occupied(key.0);
         key
     }
 
-    /// Bumps the number of items in the [`MultiStash`] by `amount`.
+    /// Puts an `amount` of `item` into the [`MultiStash`] unless it exceeds the maximum per element.
+    ///
+    /// Returns `item` alongside the error if `amount` exceeds the [`MultiStash::max_per_entry`]
+    /// in which case the [`MultiStash`] remains unchanged.
     ///
     /// # Panics
     ///
-    /// If the number of items in the [`MultiStash`] overflows.
-    fn bump_len_items(&mut self, amount: usize) {
-        self.len_items = self.len_items.checked_add(amount).unwrap_or_else(|| {
-            panic!(
-                "failed to add {} items to MultiStash of length {}",
-                amount, self.len_items
-            )
-        });
+    /// - If the number of items in the [`MultiStash`] overflows.
+    /// - If the new capacity exceeds `isize::MAX` bytes.
+    pub fn try_put(
+        &mut self,
+        amount: NonZeroUsize,
+        item: T,
+    ) -> Result<Key, (MaxPerEntryExceeded, T)> {
+        match check_max_per_entry(self.max_per_entry, amount.get()) {
+            Ok(()) => Ok(self.put(amount, item)),
+            Err(error) => Err((error, item)),
+        }
     }
 
-    /// Clears the [`MultiStash`], removing all elements.
+    /// Puts an `amount` of `item` into the [`MultiStash`] and returns a handle to it.
     ///
-    /// Note that this method has no effect on the allocated capacity of the vector.
-    pub fn clear(&mut self) {
-        self.free = 0;
-        self.len_items = 0;
-        self.len_occupied = 0;
-        self.entries.clear();
+    /// This allows to manipulate the new element without another lookup by its [`Key`].
+    ///
+    /// # Panics
+    ///
+    /// See [`MultiStash::put`].
+    pub fn put_entry(&mut self, amount: NonZeroUsize, item: T) -> OccupiedHandle<'_, T> {
+        let key = self.put(amount, item);
+        OccupiedHandle::new(self, key)
     }
 
-    /// Removes and returns the `element` at `key` and its amount of remaining items.
+    /// Puts an `amount` of `item` into the [`MultiStash`] at `index` regardless of its occupancy.
     ///
-    /// Returns `None` if `key` refers to a vacant entry or is out of bounds.
-    pub fn take_all(&mut self, key: Key) -> Option<(usize, T)> {
-        let index = key.0;
-        let taken = match self.entries.get_mut(index) {
-            None => None,
-            Some(entry) => match mem::replace(entry, Entry::from(VacantEntry::new(self.free))) {
+    /// Returns the displaced `element` and its amount of remaining items if the slot at `index`
+    /// was occupied or `None` if it was vacant or out of bounds.
+    /// If `index` is out of bounds the [`MultiStash`] grows with vacant entries up to `index`.
+    ///
+    /// # Note
+    ///
+    /// - If the slot at `index` was vacant it is unlinked from the free list which requires
+    ///   a scan of the free list.
+    /// - If the free list is non-empty or `index` is beyond the end of the [`MultiStash`]
+    ///   the free list is rebuilt which is O(n).
+    ///
+    /// # Panics
+    ///
+    /// - If the number of items in the [`MultiStash`] overflows.
+    /// - If the new capacity exceeds `isize::MAX` bytes.
+    /// - If `amount` exceeds the [`MultiStash::max_per_entry`].
+    ///
+    /// The [`MultiStash`] remains unchanged if this panics.
+    pub fn insert_at(&mut self, index: usize, amount: NonZeroUsize, item: T) -> Option<(usize, T)> {
+        ensure_max_per_entry(self.max_per_entry, amount.get());
+        let displaced_amount = self
+            .occupied_entry(Key(index))
+            .map_or(0, |entry| entry.remaining.get());
+        let len_items = checked_len_items(self.len_items - displaced_amount, amount.get());
+        let entry = Entry::from(OccupiedEntry::new(item, amount));
+        let len_entries = self.len_entries();
+        let displaced = if index < len_entries {
+            #[cfg(feature = "stats")]
+            self.stats.record_put(true);
+            match mem::replace(&mut self.entries[index], entry) {
+                Entry::Occupied(displaced) => {
+                    #[cfg(feature = "stats")]
+                    self.stats.record_take();
+                    Some((displaced.remaining.get(), displaced.item))
+                }
                 Entry::Vacant(vacant) => {
-                    *entry = Entry::from(VacantEntry::new(vacant.next_free));
+                    self.unlink_free(index, vacant.next_free);
+                    self.len_occupied += 1;
                     None
                 }
-                Entry::Occupied(occupied) => {
-                    self.free = index;
-                    let item = occupied.item;
-                    let len_taken = occupied.remaining.get();
-                    self.len_items -= len_taken;
-                    self.len_occupied -= 1;
-                    Some((len_taken, item))
-                }
-            },
+            }
+        } else {
+            let is_free_list_empty = self.free >= len_entries;
+            #[cfg(feature = "stats")]
+            let capacity = self.capacity();
+            self.reserve_entries(index - len_entries + 1);
+            #[cfg(feature = "stats")]
+            {
+                self.stats.record_put(false);
+                self.stats.record_capacity(capacity, self.capacity());
+            }
+            self.entries
+                .extend((len_entries..index).map(|_| Entry::from(VacantEntry::new(0))));
+            self.entries.push(entry);
+            if is_free_list_empty && index == len_entries {
+                self.free = self.len_entries();
+            } else {
+                self.rebuild_free_list();
+            }
+            self.len_occupied += 1;
+            None
         };
-        if self.is_empty() {
-            self.clear()
-        }
-        taken
+        self.len_items = len_items;
+        self.track_occupied(index);
+        displaced
     }
 
-    /// Bumps the amount of items of the element at `key` if any.
+    /// Replaces the `element` at `key` with an `amount` of `item` and returns the old `element`.
     ///
-    /// Returns `None` if not element is found at the `key`.
+    /// Returns the old `element` and its amount of remaining items if `key` refers to an
+    /// occupied entry. Otherwise `item` is put at `key` and `None` is returned.
+    /// Either way the slot at `key` is occupied by `item` afterwards.
+    ///
+    /// This is equivalent to [`MultiStash::insert_at`] with the index of `key`.
     ///
     /// # Panics
     ///
-    /// Panics if `amount` of the element at `key` overflows.
-    pub fn bump(&mut self, key: Key, amount: usize) -> Option<usize> {
-        let index = key.0;
-        match self.entries.get_mut(index)? {
-            Entry::Vacant(_) => None,
-            Entry::Occupied(entry) => {
-                let old_amount = entry.remaining;
-                let new_amount = old_amount.checked_add(amount).unwrap_or_else(|| {
-                    panic!(
-                        "overflow when adding {} to the amount of MultiStash element at {}",
-                        amount, index,
-                    )
-                });
-                entry.remaining = new_amount;
-                self.bump_len_items(amount);
-                Some(old_amount.get())
-            }
-        }
+    /// See [`MultiStash::insert_at`].
+    pub fn swap_take(&mut self, key: Key, amount: NonZeroUsize, item: T) -> Option<(usize, T)> {
+        self.insert_at(key.0, amount, item)
+    }
+
+    /// Unlinks the vacant entry at `index` with its `next_free` link from the free list.
+    ///
+    /// # Note
+    ///
+    /// This scans the free list up to `index`.
+    fn unlink_free(&mut self, index: usize, next_free: usize) {
+        if self.free == index {
+            self.free = next_free;
+            return;
+        }
+        let mut current = self.free;
+        while let Some(Entry::Vacant(entry)) = self.entries.get_mut(current) {
+            if entry.next_free == index {
+                entry.next_free = next_free;
+                return;
+            }
+            current = entry.next_free;
+        }
+        unreachable!(
+            "the vacant entry at {} is not linked in the free list",
+            index
+        )
+    }
+
+    /// Puts all elements yielded by `iter` into the [`MultiStash`] densely.
+    ///
+    /// Unlike [`Extend::extend`] which reuses vacant entries in the order in which they
+    /// have been freed, this first fills up all vacant entries in ascending key order
+    /// and only then appends the remaining elements at the end of the [`MultiStash`].
+    /// This way the key of every inserted element is deterministic and predictable.
+    ///
+    /// # Note
+    ///
+    /// This scans all entries of the [`MultiStash`] once and therefore is O(n).
+    ///
+    /// # Panics
+    ///
+    /// - If the number of items in the [`MultiStash`] overflows.
+    /// - If the new capacity exceeds `isize::MAX` bytes.
+    /// - If any amount exceeds the [`MultiStash::max_per_entry`].
+    ///
+    /// All elements put before a panic remain in the [`MultiStash`] which stays valid.
+    pub fn extend_dense<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (NonZeroUsize, T)>,
+    {
+        let mut iter = iter.into_iter();
+        let mut guard = RestoreGuard::new(self);
+        for index in 0..guard.stash.len_entries() {
+            if !matches!(guard.stash.entries[index], Entry::Vacant(_)) {
+                continue;
+            }
+            let Some((amount, item)) = iter.next() else {
+                break;
+            };
+            ensure_max_per_entry(guard.stash.max_per_entry, amount.get());
+            guard.stash.bump_len_items(amount.get());
+            guard.stash.entries[index] = Entry::from(OccupiedEntry::new(item, amount));
+            guard.dirty = true;
+            guard.stash.len_occupied += 1;
+            guard.stash.track_occupied(index);
+            #[cfg(feature = "stats")]
+            guard.stash.stats.record_put(true);
+        }
+        drop(guard);
+        for (amount, item) in iter {
+            self.put(amount, item);
+        }
+    }
+
+    /// Puts all elements yielded by the exact size iterator `iter` into the [`MultiStash`].
+    ///
+    /// Unlike [`Extend::extend`] this reserves exactly the required capacity upfront
+    /// using the exact length of `iter` so that no reallocations happen while inserting.
+    ///
+    /// # Panics
+    ///
+    /// - If the number of items in the [`MultiStash`] overflows.
+    /// - If the new capacity exceeds `isize::MAX` bytes.
+    /// - If any amount exceeds the [`MultiStash::max_per_entry`].
+    pub fn extend_exact<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (NonZeroUsize, T)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        self.reserve_exact(iter.len());
+        for (amount, item) in iter {
+            self.put(amount, item);
+        }
+    }
+
+    /// Puts elements yielded by `iter` into the [`MultiStash`] until one does not fit.
+    ///
+    /// Returns the number of elements that have been put into the [`MultiStash`].
+    ///
+    /// # Errors
+    ///
+    /// If an element would overflow the number of items in the [`MultiStash`] or exceed the
+    /// [`MultiStash::max_per_entry`]. The error holds the number of elements put before
+    /// as well as the rejected amount and element.
+    ///
+    /// # Note
+    ///
+    /// - The rest of `iter` is not consumed after the rejected element.
+    /// - All elements put before are left in place.
+    ///
+    /// # Panics
+    ///
+    /// If the new capacity exceeds `isize::MAX` bytes.
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<usize, (usize, (NonZeroUsize, T))>
+    where
+        I: IntoIterator<Item = (NonZeroUsize, T)>,
+    {
+        let mut inserted = 0;
+        for (amount, item) in iter {
+            let exceeds_max = self
+                .max_per_entry
+                .is_some_and(|max| amount.get() > max.get());
+            if exceeds_max || self.len_items.checked_add(amount.get()).is_none() {
+                return Err((inserted, (amount, item)));
+            }
+            self.put(amount, item);
+            inserted += 1;
+        }
+        Ok(inserted)
+    }
+
+    /// Merges all elements of `other` into the [`MultiStash`] preserving their keys.
+    ///
+    /// - Elements only stored in `other` are inserted at their original key.
+    /// - Elements only stored in `self` are left unchanged.
+    /// - For elements stored at the same key in both, `combine` is called with the element
+    ///   and amount of `self` as well as the element and amount of `other`. It reconciles
+    ///   both in place so that the amount of `self` afterwards is the merged amount.
+    ///
+    /// # Note
+    ///
+    /// - This is meant for stashes that share the same key space, e.g. replicas.
+    /// - Both entry vectors are walked in lockstep and the free list is rebuilt at most once
+    ///   so that this is O(n + m).
+    ///
+    /// # Panics
+    ///
+    /// - If the number of items in the [`MultiStash`] overflows.
+    /// - If the new capacity exceeds `isize::MAX` bytes.
+    /// - If any merged amount exceeds the [`MultiStash::max_per_entry`].
+    pub fn merge_with<F>(&mut self, other: MultiStash<T>, mut combine: F)
+    where
+        F: FnMut(&mut T, T, &mut NonZeroUsize, NonZeroUsize),
+    {
+        let len_entries = self.len_entries();
+        let new_len_entries = other.max_occupied.map_or(0, |max| max + 1);
+        let mut guard = RestoreGuard::new(self);
+        if new_len_entries > len_entries {
+            #[cfg(feature = "stats")]
+            let capacity = guard.stash.capacity();
+            guard.dirty = true;
+            guard.stash.reserve_entries(new_len_entries - len_entries);
+            guard
+                .stash
+                .entries
+                .resize_with(new_len_entries, || Entry::from(VacantEntry::new(0)));
+            #[cfg(feature = "stats")]
+            guard
+                .stash
+                .stats
+                .record_capacity(capacity, guard.stash.capacity());
+        }
+        for (index, entry) in other.entries.into_iter().enumerate() {
+            let Entry::Occupied(other) = entry else {
+                continue;
+            };
+            match &mut guard.stash.entries[index] {
+                Entry::Occupied(entry) => {
+                    let old_remaining = entry.remaining;
+                    let mut remaining = old_remaining;
+                    combine(&mut entry.item, other.item, &mut remaining, other.remaining);
+                    ensure_max_per_entry(guard.stash.max_per_entry, remaining.get());
+                    guard.stash.len_items = checked_len_items(
+                        guard.stash.len_items - old_remaining.get(),
+                        remaining.get(),
+                    );
+                    entry.remaining = remaining;
+                }
+                slot @ Entry::Vacant(_) => {
+                    ensure_max_per_entry(guard.stash.max_per_entry, other.remaining.get());
+                    let len_items = checked_len_items(guard.stash.len_items, other.remaining.get());
+                    *slot = Entry::from(other);
+                    guard.dirty = true;
+                    guard.stash.len_items = len_items;
+                    guard.stash.len_occupied += 1;
+                    guard.stash.track_occupied(index);
+                    #[cfg(feature = "stats")]
+                    guard.stash.stats.record_put(index < len_entries);
+                }
+            }
+        }
+    }
+
+    /// Rebuilds the free list from all vacant entries of the [`MultiStash`].
+    ///
+    /// Afterwards the vacant entries are linked in ascending key order.
+    ///
+    /// # Note
+    ///
+    /// This is O(n) and usually only required to repair the [`MultiStash`] after raw edits.
+    pub fn rebuild_free_list(&mut self) {
+        self.free = self.len_entries();
+        for (index, entry) in self.entries.iter_mut().enumerate().rev() {
+            if let Entry::Vacant(entry) = entry {
+                entry.next_free = self.free;
+                self.free = index;
+            }
+        }
+    }
+
+    /// Bumps the number of items in the [`MultiStash`] by `amount`.
+    ///
+    /// # Panics
+    ///
+    /// If the number of items in the [`MultiStash`] overflows.
+    fn bump_len_items(&mut self, amount: usize) {
+        self.len_items = checked_len_items(self.len_items, amount);
+    }
+
+    /// Updates the first and last occupied entries after the entry at `index` became occupied.
+    fn track_occupied(&mut self, index: usize) {
+        self.min_occupied = Some(self.min_occupied.map_or(index, |min| min.min(index)));
+        self.max_occupied = Some(self.max_occupied.map_or(index, |max| max.max(index)));
+    }
+
+    /// Updates the first and last occupied entries after the entry at `index` became vacant.
+    ///
+    /// # Note
+    ///
+    /// This is O(1) unless the first or last occupied entry became vacant
+    /// in which case the entries are scanned for the new first or last occupied entry.
+    fn track_vacated(&mut self, index: usize) {
+        let is_occupied = |entry: &Entry<T>| matches!(entry, Entry::Occupied(_));
+        if self.min_occupied == Some(index) {
+            self.min_occupied = self.entries[index..]
+                .iter()
+                .position(is_occupied)
+                .map(|offset| index + offset);
+        }
+        if self.max_occupied == Some(index) {
+            self.max_occupied = self.entries[..index].iter().rposition(is_occupied);
+        }
+    }
+
+    /// Recomputes the number of items and occupied entries from all entries of the [`MultiStash`].
+    ///
+    /// This also recomputes the first and last occupied entries.
+    ///
+    /// # Note
+    ///
+    /// This is O(n) and usually only required to repair the [`MultiStash`] after raw edits.
+    ///
+    /// # Panics
+    ///
+    /// If the number of items in the [`MultiStash`] overflows.
+    pub fn recompute_counters(&mut self) {
+        let mut len_items = 0;
+        let mut len_occupied = 0;
+        for entry in &self.entries {
+            if let Entry::Occupied(entry) = entry {
+                len_items = checked_len_items(len_items, entry.remaining.get());
+                len_occupied += 1;
+            }
+        }
+        self.len_items = len_items;
+        self.len_occupied = len_occupied;
+        self.recompute_occupied_bounds();
+    }
+
+    /// Recomputes the first and last occupied entries by scanning all entries.
+    fn recompute_occupied_bounds(&mut self) {
+        let is_occupied = |entry: &Entry<T>| matches!(entry, Entry::Occupied(_));
+        self.min_occupied = self.entries.iter().position(is_occupied);
+        self.max_occupied = self.entries.iter().rposition(is_occupied);
+    }
+
+    /// Shrinks the number of items in the [`MultiStash`] by `amount`.
+    ///
+    /// # Note
+    ///
+    /// The number of items saturates at zero if the invariants of the [`MultiStash`] are violated.
+    fn shrink_len_items(&mut self, amount: usize) {
+        debug_assert!(
+            amount <= self.len_items,
+            "failed to remove {} items from MultiStash of length {}",
+            amount,
+            self.len_items,
+        );
+        self.len_items = self.len_items.saturating_sub(amount);
+    }
+
+    /// Shrinks the number of occupied entries in the [`MultiStash`] by one.
+    ///
+    /// # Note
+    ///
+    /// The number of occupied entries saturates at zero if the invariants of the [`MultiStash`] are violated.
+    fn shrink_len_occupied(&mut self) {
+        debug_assert!(
+            self.len_occupied > 0,
+            "failed to remove an element from empty MultiStash"
+        );
+        self.len_occupied = self.len_occupied.saturating_sub(1);
+    }
+
+    /// Clears the [`MultiStash`], removing all elements.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the vector.
+    pub fn clear(&mut self) {
+        self.free = 0;
+        self.len_items = 0;
+        self.len_occupied = 0;
+        self.min_occupied = None;
+        self.max_occupied = None;
+        self.entries.clear();
+    }
+
+    /// Pops vacant entries off the end of the [`MultiStash`] while they are at the head of the free list.
+    ///
+    /// This keeps the entries tight for back-to-front removal patterns.
+    /// Vacant entries at the end that are not at the head of the free list are kept
+    /// since unlinking them from the free list would require a scan.
+    fn pop_vacant_tail(&mut self) {
+        while self.len_entries().checked_sub(1) == Some(self.free) {
+            match self.entries.pop() {
+                Some(Entry::Vacant(entry)) => self.free = entry.next_free,
+                _ => unreachable!(
+                    "asserted that the entry at `self.free` ({}) is vacant",
+                    self.free
+                ),
+            }
+        }
+        // Vacant entries may still link to popped entries, so reset to the end-of-list marker.
+        self.free = self.free.min(self.len_entries());
+    }
+
+    /// Removes all vacant entries at the end of the [`MultiStash`] and returns their number.
+    ///
+    /// Unlike [`MultiStash::take_all`] this also reclaims vacant entries that are not
+    /// at the head of the free list. The free list is rebuilt if any entry was reclaimed.
+    ///
+    /// # Note
+    ///
+    /// This has no effect on the allocated capacity of the vector
+    /// so that the [`MultiStash`] can quickly grow again.
+    /// Rebuilding the free list is O(n).
+    pub fn truncate_vacant_tail(&mut self) -> usize {
+        let len_entries = self.len_entries();
+        let new_len = self
+            .entries
+            .iter()
+            .rposition(|entry| matches!(entry, Entry::Occupied(_)))
+            .map_or(0, |index| index + 1);
+        if new_len == len_entries {
+            return 0;
+        }
+        self.entries.truncate(new_len);
+        self.rebuild_free_list();
+        len_entries - new_len
+    }
+
+    /// Moves all elements to the front of the [`MultiStash`] removing all vacant entries.
+    ///
+    /// Returns the old and new [`Key`] of every moved element in ascending key order.
+    ///
+    /// # Note
+    ///
+    /// - The compaction is stable: the elements retain their relative order
+    ///   so that iteration order is unchanged.
+    /// - The keys of moved elements are invalidated and must be remapped by the caller.
+    /// - This has no effect on the allocated capacity of the vector.
+    pub fn compact(&mut self) -> Vec<(Key, Key)> {
+        let mut remap = Vec::new();
+        let mut len_compacted = 0;
+        for index in 0..self.len_entries() {
+            if let Entry::Occupied(_) = self.entries[index] {
+                if index != len_compacted {
+                    self.entries.swap(index, len_compacted);
+                    remap.push((Key(index), Key(len_compacted)));
+                }
+                len_compacted += 1;
+            }
+        }
+        self.entries.truncate(len_compacted);
+        self.free = len_compacted;
+        self.min_occupied = (len_compacted > 0).then_some(0);
+        self.max_occupied = len_compacted.checked_sub(1);
+        remap
+    }
+
+    /// Removes all elements for which `f` returns `false` and compacts the remaining ones.
+    ///
+    /// Returns the old and new [`Key`] of every moved element in ascending key order.
+    /// This is done in a single pass over all entries.
+    ///
+    /// # Note
+    ///
+    /// - The keys of moved elements are invalidated and must be remapped by the caller.
+    /// - Like [`MultiStash::compact`] this is stable and has no effect on the allocated capacity.
+    /// - If `f` panics the [`MultiStash`] remains valid but only partially compacted.
+    pub fn retain_compact<F>(&mut self, mut f: F) -> Vec<(Key, Key)>
+    where
+        F: FnMut(Key, usize, &mut T) -> bool,
+    {
+        let mut remap = Vec::new();
+        let mut len_compacted = 0;
+        let mut guard = RestoreGuard::new(self);
+        guard.dirty = true;
+        for index in 0..guard.stash.len_entries() {
+            let Entry::Occupied(entry) = &mut guard.stash.entries[index] else {
+                continue;
+            };
+            if f(Key(index), entry.remaining.get(), &mut entry.item) {
+                if index != len_compacted {
+                    guard.stash.entries.swap(index, len_compacted);
+                    remap.push((Key(index), Key(len_compacted)));
+                }
+                len_compacted += 1;
+                continue;
+            }
+            let removed = mem::replace(
+                &mut guard.stash.entries[index],
+                Entry::from(VacantEntry::new(0)),
+            );
+            if let Entry::Occupied(removed) = &removed {
+                guard.stash.shrink_len_items(removed.remaining.get());
+                guard.stash.shrink_len_occupied();
+                #[cfg(feature = "stats")]
+                guard.stash.stats.record_take();
+            }
+            drop(removed);
+        }
+        guard.stash.entries.truncate(len_compacted);
+        drop(guard);
+        if self.is_empty() {
+            self.clear()
+        }
+        remap
+    }
+
+    /// Removes and returns the `element` at `key` and its amount of remaining items.
+    ///
+    /// Returns `None` if `key` refers to a vacant entry or is out of bounds.
+    ///
+    /// If the removed `element` was the last entry of the [`MultiStash`] its slot
+    /// is reclaimed instead of being linked into the free list.
+    pub fn take_all(&mut self, key: Key) -> Option<(usize, T)> {
+        let taken = self
+            .take_entry(key.0)
+            .map(|entry| (entry.remaining.get(), entry.item));
+        if self.is_empty() {
+            self.clear()
+        }
+        taken
+    }
+
+    /// Removes and returns the `element` at `index` and its amount of remaining items.
+    ///
+    /// This is equivalent to [`MultiStash::take_all`] with `Key::from(index)`.
+    pub fn take_all_at(&mut self, index: usize) -> Option<(usize, T)> {
+        self.take_all(Key(index))
+    }
+
+    /// Removes and returns the `element` at `key` if `pred` holds for it.
+    ///
+    /// `pred` is called with the remaining items and the `element` at `key`.
+    /// Returns `None` and leaves the [`MultiStash`] unchanged if `pred` returns `false`
+    /// or if `key` refers to a vacant entry or is out of bounds.
+    pub fn take_if<F>(&mut self, key: Key, pred: F) -> Option<(usize, T)>
+    where
+        F: FnOnce(usize, &T) -> bool,
+    {
+        let (remaining, item) = self.get(key)?;
+        if !pred(remaining, item) {
+            return None;
+        }
+        self.take_all(key)
+    }
+
+    /// Removes and returns the `element` at `key` with details about the freed slot.
+    ///
+    /// Returns `None` if `key` refers to a vacant entry or is out of bounds.
+    ///
+    /// # Note
+    ///
+    /// This behaves like [`MultiStash::take_all`] but additionally reports the index
+    /// of the slot that the next [`MultiStash::put`] reuses. See [`TakeResult`].
+    pub fn take_all_detailed(&mut self, key: Key) -> Option<TakeResult<T>> {
+        let (remaining, item) = self.take_all(key)?;
+        Some(TakeResult::new(remaining, item, self.next_free_key().0))
+    }
+
+    /// Removes and returns the occupied entry at `index` if any.
+    ///
+    /// # Note
+    ///
+    /// Unlike [`MultiStash::take_all`] this does not reset the [`MultiStash`] if it became empty.
+    fn take_entry(&mut self, index: usize) -> Option<OccupiedEntry<T>> {
+        let entry = self.entries.get_mut(index)?;
+        match mem::replace(entry, Entry::from(VacantEntry::new(self.free))) {
+            Entry::Vacant(vacant) => {
+                *entry = Entry::from(VacantEntry::new(vacant.next_free));
+                None
+            }
+            Entry::Occupied(occupied) => {
+                self.free = index;
+                self.shrink_len_items(occupied.remaining.get());
+                self.shrink_len_occupied();
+                self.track_vacated(index);
+                self.pop_vacant_tail();
+                #[cfg(feature = "stats")]
+                self.stats.record_take();
+                Some(occupied)
+            }
+        }
+    }
+
+    /// Removes and returns all elements for which `f` returns `true`.
+    ///
+    /// The elements are returned with their keys and remaining items in ascending key order.
+    /// The keys of all other elements remain valid.
+    /// This is done in a single pass over all entries.
+    pub fn take_where<F>(&mut self, mut f: F) -> Vec<(Key, usize, T)>
+    where
+        F: FnMut(Key, usize, &T) -> bool,
+    {
+        let mut taken = Vec::new();
+        let mut index = 0;
+        while index < self.len_entries() {
+            if let Entry::Occupied(entry) = &self.entries[index] {
+                if f(Key(index), entry.remaining.get(), &entry.item) {
+                    if let Some(entry) = self.take_entry(index) {
+                        taken.push((Key(index), entry.remaining.get(), entry.item));
+                    }
+                }
+            }
+            index += 1;
+        }
+        if self.is_empty() {
+            self.clear()
+        }
+        taken
+    }
+
+    /// Removes all elements of the [`MultiStash`] and yields them in the order given by `cmp`.
+    ///
+    /// The comparator is applied to the remaining items and element of each entry.
+    /// Elements that compare equal are yielded in ascending key order.
+    ///
+    /// # Note
+    ///
+    /// - The [`MultiStash`] is emptied eagerly, even if the returned iterator is not consumed.
+    /// - All elements are collected and sorted upfront which is O(n log n).
+    pub fn drain_sorted_by<F>(&mut self, mut cmp: F) -> impl Iterator<Item = (Key, usize, T)>
+    where
+        F: FnMut(&(usize, &T), &(usize, &T)) -> Ordering,
+    {
+        let mut drained = self.take_where(|_, _, _| true);
+        drained.sort_by(|(_, lhs_remaining, lhs), (_, rhs_remaining, rhs)| {
+            cmp(&(*lhs_remaining, lhs), &(*rhs_remaining, rhs))
+        });
+        drained.into_iter()
+    }
+
+    /// Removes and returns all elements whose bit is set in `bitmap`.
+    ///
+    /// The `bitmap` is packed the same way as the one returned by [`MultiStash::occupancy_bitmap`].
+    /// The elements are returned with their keys and remaining items in ascending key order.
+    /// This is done in a single pass over the set bits.
+    ///
+    /// # Note
+    ///
+    /// - Bits referring to vacant entries are ignored.
+    /// - Bits referring to out of bounds entries are ignored.
+    /// - If `bitmap` is shorter than required the missing bits are treated as unset.
+    pub fn take_bitmap(&mut self, bitmap: &[u64]) -> Vec<(Key, usize, T)> {
+        let len_words = self.len_entries().div_ceil(64);
+        let mut taken = Vec::new();
+        for (word_index, &word) in bitmap.iter().enumerate().take(len_words) {
+            let mut word = word;
+            while word != 0 {
+                let index = word_index * 64 + word.trailing_zeros() as usize;
+                word &= word - 1;
+                if let Some(entry) = self.take_entry(index) {
+                    taken.push((Key(index), entry.remaining.get(), entry.item));
+                }
+            }
+        }
+        if self.is_empty() {
+            self.clear()
+        }
+        taken
+    }
+
+    /// Splits off all elements for which `f` returns `false` into a new [`MultiStash`].
+    ///
+    /// The elements for which `f` returns `true` are kept and their keys remain valid.
+    /// This is done in a single pass over all entries.
+    ///
+    /// # Note
+    ///
+    /// The returned [`MultiStash`] is compacted and thus assigns fresh keys to the split off elements.
+    pub fn split_retain<F>(&mut self, mut f: F) -> MultiStash<T>
+    where
+        F: FnMut(Key, usize, &T) -> bool,
+    {
+        let mut removed = MultiStash::new();
+        removed.max_per_entry = self.max_per_entry;
+        let mut index = 0;
+        while index < self.len_entries() {
+            if let Entry::Occupied(entry) = &self.entries[index] {
+                if !f(Key(index), entry.remaining.get(), &entry.item) {
+                    if let Some(entry) = self.take_entry(index) {
+                        removed.put(entry.remaining, entry.item);
+                    }
+                }
+            }
+            index += 1;
+        }
+        if self.is_empty() {
+            self.clear()
+        }
+        removed
+    }
+
+    /// Removes and returns the `element` at `key` and its amount of remaining items.
+    ///
+    /// Returns `Ok(None)` if `key` refers to a vacant entry or is out of bounds.
+    ///
+    /// # Errors
+    ///
+    /// If the counters of the [`MultiStash`] would underflow in which case it remains unchanged.
+    pub fn try_take_all(&mut self, key: Key) -> Result<Option<(usize, T)>, Corruption> {
+        if let Some(entry) = self.occupied_entry(key) {
+            if entry.remaining.get() > self.len_items || self.len_occupied == 0 {
+                return Err(Corruption::new(key));
+            }
+        }
+        Ok(self.take_all(key))
+    }
+
+    /// Bumps the amount of items of the element at `key` if any.
+    ///
+    /// Returns `None` if not element is found at the `key`.
+    ///
+    /// # Panics
+    ///
+    /// - If `amount` of the element at `key` overflows.
+    /// - If the number of items in the [`MultiStash`] overflows.
+    /// - If the new amount of the element at `key` exceeds the [`MultiStash::max_per_entry`].
+    ///
+    /// The [`MultiStash`] remains unchanged if this panics.
+    pub fn bump(&mut self, key: Key, amount: usize) -> Option<usize> {
+        let index = key.0;
+        let max_per_entry = self.max_per_entry;
+        let len_items = self.len_items;
+        let entry = self.occupied_entry_mut(key)?;
+        let old_amount = entry.remaining;
+        let new_amount = old_amount.checked_add(amount).unwrap_or_else(|| {
+            panic!(
+                "overflow when adding {} to the amount of MultiStash element at {}",
+                amount, index,
+            )
+        });
+        ensure_max_per_entry(max_per_entry, new_amount.get());
+        let len_items = checked_len_items(len_items, amount);
+        entry.remaining = new_amount;
+        self.len_items = len_items;
+        Some(old_amount.get())
+    }
+
+    /// Bumps the amount of items of the element at `key` unless it exceeds the maximum per element.
+    ///
+    /// Returns `Ok(None)` if not element is found at the `key` and an error if the new amount
+    /// of the element would exceed the [`MultiStash::max_per_entry`].
+    /// The [`MultiStash`] remains unchanged if this returns an error.
+    ///
+    /// # Panics
+    ///
+    /// - If `amount` of the element at `key` overflows and there is no maximum per element.
+    /// - If the number of items in the [`MultiStash`] overflows.
+    pub fn try_bump(
+        &mut self,
+        key: Key,
+        amount: usize,
+    ) -> Result<Option<usize>, MaxPerEntryExceeded> {
+        if let Some((remaining, _)) = self.get(key) {
+            check_max_per_entry(self.max_per_entry, remaining.saturating_add(amount))?;
+        }
+        Ok(self.bump(key, amount))
+    }
+
+    /// Bumps the amount of items of the element at `key` by `amount` if `pred` holds for it.
+    ///
+    /// `pred` is called with the remaining items and the element at `key`.
+    /// Returns the old amount of items on success or `None` and leaves the [`MultiStash`]
+    /// unchanged if `pred` returns `false` or if no element is found at the `key`.
+    ///
+    /// # Panics
+    ///
+    /// See [`MultiStash::bump`].
+    pub fn bump_if<F>(&mut self, key: Key, amount: usize, pred: F) -> Option<usize>
+    where
+        F: FnOnce(usize, &T) -> bool,
+    {
+        let (remaining, item) = self.get(key)?;
+        if !pred(remaining, item) {
+            return None;
+        }
+        self.bump(key, amount)
+    }
+
+    /// Returns an iterator over the elements of the [`MultiStash`] allowing to change their remaining items.
+    ///
+    /// The iterator yields all elements, their keys and mutable handles to their remaining items
+    /// from start to end. Changes via the handles keep [`MultiStash::len_items`] up to date.
+    pub fn counts_mut(&mut self) -> impl DoubleEndedIterator<Item = (Key, RemainingMut<'_>, &T)> {
+        let len_items = Cell::from_mut(&mut self.len_items);
+        let max_per_entry = self.max_per_entry;
+        self.entries
+            .iter_mut()
+            .enumerate()
+            .filter_map(move |(index, entry)| match entry {
+                Entry::Vacant(_) => None,
+                Entry::Occupied(OccupiedEntry { remaining, item }) => Some((
+                    Key(index),
+                    RemainingMut::new(remaining, len_items, max_per_entry),
+                    &*item,
+                )),
+            })
+    }
+
+    /// Returns an iterator over the elements of the [`MultiStash`] with keys in `start..end`.
+    ///
+    /// The iterator yields mutable references to all elements, their keys and remaining items
+    /// within the range. Bounds that are out of range are clamped to the valid range.
+    pub fn range_mut(
+        &mut self,
+        start: Key,
+        end: Key,
+    ) -> impl DoubleEndedIterator<Item = (Key, usize, &mut T)> {
+        let end = end.0.min(self.len_entries());
+        let start = start.0.min(end);
+        self.entries[start..end]
+            .iter_mut()
+            .enumerate()
+            .filter_map(move |(offset, entry)| match entry {
+                Entry::Vacant(_) => None,
+                Entry::Occupied(entry) => {
+                    Some((Key(start + offset), entry.remaining.get(), &mut entry.item))
+                }
+            })
+    }
+
+    /// Returns a packed bitmap of the occupied entries of the [`MultiStash`].
+    ///
+    /// Bit `i % 64` of word `i / 64` is set if and only if the entry at `Key(i)` is occupied.
+    /// The bitmap consists of `ceil(n / 64)` words where `n` is the number of entries.
+    pub fn occupancy_bitmap(&self) -> Vec<u64> {
+        let mut bitmap = vec![0_u64; self.len_entries().div_ceil(64)];
+        for (index, entry) in self.entries.iter().enumerate() {
+            if let Entry::Occupied(_) = entry {
+                bitmap[index / 64] |= 1 << (index % 64);
+            }
+        }
+        bitmap
+    }
+
+    /// Returns the range of indices spanning all occupied entries of the [`MultiStash`].
+    fn occupied_span(&self) -> core::ops::Range<usize> {
+        match (self.min_occupied, self.max_occupied) {
+            (Some(min), Some(max)) => min..max + 1,
+            _ => 0..0,
+        }
+    }
+
+    /// Calls `f` on all elements of the [`MultiStash`] in ascending key order until it fails.
+    ///
+    /// Returns the first error returned by `f` if any.
+    ///
+    /// # Note
+    ///
+    /// Vacant entries before the first and after the last occupied entry are not visited.
+    pub fn try_for_each<E, F>(&self, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(Key, usize, &T) -> Result<(), E>,
+    {
+        let span = self.occupied_span();
+        let start = span.start;
+        for (index, entry) in self.entries[span].iter().enumerate() {
+            if let Entry::Occupied(entry) = entry {
+                f(Key(start + index), entry.remaining.get(), &entry.item)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls `f` on all elements of the [`MultiStash`] mutably in ascending key order until it fails.
+    ///
+    /// Returns the first error returned by `f` if any.
+    ///
+    /// # Note
+    ///
+    /// Vacant entries before the first and after the last occupied entry are not visited.
+    pub fn try_for_each_mut<E, F>(&mut self, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(Key, usize, &mut T) -> Result<(), E>,
+    {
+        let span = self.occupied_span();
+        let start = span.start;
+        for (index, entry) in self.entries[span].iter_mut().enumerate() {
+            if let Entry::Occupied(entry) = entry {
+                f(Key(start + index), entry.remaining.get(), &mut entry.item)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls `f` on every element of the [`MultiStash`] in ascending key order.
+    ///
+    /// This is useful to prepare elements in bulk, for example to preallocate
+    /// elements that are collections themselves via `stash.reserve_each(|v| v.reserve(16))`.
+    pub fn reserve_each<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T),
+    {
+        for (_, _, item) in self.iter_mut() {
+            f(item)
+        }
+    }
+
+    /// Folds all elements of the [`MultiStash`] with their remaining items into an accumulator.
+    ///
+    /// This is useful to compute weighted aggregates such as the total value of all items.
+    pub fn weighted_fold<A, F>(&self, init: A, mut f: F) -> A
+    where
+        F: FnMut(A, usize, &T) -> A,
+    {
+        self.iter()
+            .fold(init, |acc, (_, remaining, item)| f(acc, remaining, item))
+    }
+
+    /// Groups the elements of the [`MultiStash`] by the key computed by `f`.
+    ///
+    /// Within each group the elements are ordered by ascending [`Key`].
+    ///
+    /// # Note
+    ///
+    /// This allocates a [`HashMap`] and a [`Vec`] for every group.
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[cfg(feature = "std")]
+    pub fn group_by_key<K, F>(
+        &self,
+        mut f: F,
+    ) -> std::collections::HashMap<K, Vec<(Key, usize, &T)>>
+    where
+        K: Eq + core::hash::Hash,
+        F: FnMut(&T) -> K,
+    {
+        let mut groups = std::collections::HashMap::<K, Vec<_>>::new();
+        for (key, remaining, item) in self {
+            groups
+                .entry(f(item))
+                .or_default()
+                .push((key, remaining, item));
+        }
+        groups
+    }
+
+    /// Moves all elements of the [`MultiStash`] into the `target` collection.
+    ///
+    /// The elements are appended with their keys and remaining items from start to end.
+    /// The `target` may reserve capacity upfront since the exact number of elements is known.
+    pub fn collect_into<C>(self, target: &mut C)
+    where
+        C: Extend<(Key, usize, T)>,
+    {
+        target.extend(self);
     }
 
     /// Returns an iterator over the elements of the [`MultiStash`].
     ///
     /// The iterator yields all elements, their keys and remaining items from start to end.
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter::new(self)
     }
 
+    /// Splits the [`MultiStash`] into two mutable views before and from `key`.
+    ///
+    /// The first view spans all entries before `key` and the second all entries from `key` on.
+    /// Both views use the keys of the [`MultiStash`]. No elements are moved.
+    ///
+    /// # Note
+    ///
+    /// - If `key` is out of bounds the second view is empty.
+    /// - Computing the number of elements of the views scans the entries before `key`.
+    pub fn split_at_key(&mut self, key: Key) -> (SubStashMut<'_, T>, SubStashMut<'_, T>) {
+        let mid = key.0.min(self.len_entries());
+        let (lhs, rhs) = self.entries.split_at_mut(mid);
+        let len_lhs = lhs
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Occupied(_)))
+            .count();
+        (
+            SubStashMut::new(0, len_lhs, lhs),
+            SubStashMut::new(mid, self.len_occupied - len_lhs, rhs),
+        )
+    }
+
+    /// Returns an iterator over the elements of both `a` and `b` tagged with their origin.
+    ///
+    /// The iterator yields all elements of `a` followed by all elements of `b`.
+    /// Each element is yielded with `true` if it stems from `b`, its key, and its remaining items.
+    ///
+    /// # Note
+    ///
+    /// Keys are only unique per [`MultiStash`] and must be disambiguated by the tag.
+    pub fn chain_tagged<'a>(
+        a: &'a MultiStash<T>,
+        b: &'a MultiStash<T>,
+    ) -> impl Iterator<Item = (bool, Key, usize, &'a T)> {
+        let tag = |is_second| move |(key, remaining, item)| (is_second, key, remaining, item);
+        a.iter().map(tag(false)).chain(b.iter().map(tag(true)))
+    }
+
+    /// Returns an iterator over the elements of the [`MultiStash`] and their occupied rank.
+    ///
+    /// The iterator yields the 0-based rank among all elements, their keys, remaining items
+    /// and the elements from start to end.
+    pub fn enumerate_occupied(&self) -> impl Iterator<Item = (usize, Key, usize, &T)> {
+        self.iter()
+            .enumerate()
+            .map(|(rank, (key, remaining, item))| (rank, key, remaining, item))
+    }
+
+    /// Returns an iterator that moves out the elements of the [`MultiStash`] in stack order.
+    ///
+    /// The iterator yields all elements, their keys and remaining items from end to start
+    /// which is the natural pop order when using the [`MultiStash`] as a stack.
+    pub fn into_stack_iter(self) -> impl ExactSizeIterator<Item = (Key, usize, T)> {
+        self.into_iter().rev()
+    }
+
     /// Returns an iterator over the elements of the [`MultiStash`].
     ///
     /// The iterator yields mutable references to all elements, their keys and remaining items from start to end.
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut::new(self)
     }
 }
 
+impl<T: Copy> MultiStash<T> {
+    /// Returns an iterator over copies of the elements of the [`MultiStash`].
+    ///
+    /// The iterator yields all elements, their keys and remaining items from start to end.
+    pub fn iter_copied(&self) -> impl DoubleEndedIterator<Item = (Key, usize, T)> + '_ {
+        self.iter()
+            .map(|(key, remaining, item)| (key, remaining, *item))
+    }
+}
+
 impl<T: Clone> MultiStash<T> {
+    /// Returns a clone of all the raw entries of the [`MultiStash`].
+    ///
+    /// This clones every entry including vacant ones so that the exact layout of
+    /// the [`MultiStash`] including its free list links is captured.
+    pub fn entries_snapshot(&self) -> Vec<Entry<T>> {
+        self.entries.clone()
+    }
+
+    /// Returns a new [`MultiStash`] with clones of only the elements at `keys`.
+    ///
+    /// The cloned elements keep their keys so that keys of the original [`MultiStash`]
+    /// remain valid for the subset. All other slots up to the highest cloned key are vacant.
+    /// Keys that refer to vacant entries or are out of bounds are skipped.
+    ///
+    /// # Note
+    ///
+    /// The configuration of the [`MultiStash`] is retained but its statistics are not.
+    pub fn clone_subset(&self, keys: &[Key]) -> MultiStash<T> {
+        let mut subset = Self::new();
+        subset.max_per_entry = self.max_per_entry;
+        subset.growth_increment = self.growth_increment;
+        let len_entries = keys
+            .iter()
+            .filter(|key| self.is_valid(**key))
+            .map(|key| key.0 + 1)
+            .max()
+            .unwrap_or(0);
+        subset.entries = (0..len_entries)
+            .map(|_| Entry::from(VacantEntry::new(0)))
+            .collect();
+        for key in keys {
+            let Some(entry) = self.occupied_entry(*key) else {
+                continue;
+            };
+            let slot = &mut subset.entries[key.0];
+            if let Entry::Vacant(_) = slot {
+                subset.len_items += entry.remaining.get();
+                subset.len_occupied += 1;
+            }
+            *slot = Entry::from(entry.clone());
+        }
+        subset.rebuild_free_list();
+        subset.recompute_occupied_bounds();
+        subset
+    }
+
     /// Returns a single item of the `element` at `key`
     /// and the amount of remaining items after this operation.
     ///
     /// Remove the `element` if no items are left after this operation.
     /// Returns `None` if `key` refers to a vacant entry or is out of bounds.
+    ///
+    /// If the removed `element` was the last entry of the [`MultiStash`] its slot
+    /// is reclaimed instead of being linked into the free list.
+    ///
+    /// The [`MultiStash`] remains unchanged if cloning the `element` panics.
     pub fn take_one(&mut self, key: Key) -> Option<(usize, T)> {
         let index = key.0;
-        let taken = match self.entries.get_mut(index) {
-            None => None,
-            Some(entry) => match mem::replace(entry, Entry::from(VacantEntry::new(self.free))) {
-                Entry::Vacant(vacant) => {
-                    *entry = Entry::from(VacantEntry::new(vacant.next_free));
-                    None
-                }
-                Entry::Occupied(occupied) => {
-                    let item = occupied.item;
-                    self.len_items -= 1;
-                    match NonZeroUsize::new(occupied.remaining.get().wrapping_sub(1)) {
-                        Some(remaining) => {
-                            *entry = Entry::from(OccupiedEntry::new(item.clone(), remaining));
-                            Some((remaining.get(), item))
-                        }
-                        None => {
-                            self.len_occupied -= 1;
-                            self.free = index;
-                            Some((0, item))
-                        }
-                    }
-                }
-            },
+        let entry = self.occupied_entry_mut(key)?;
+        let taken = match NonZeroUsize::new(entry.remaining.get() - 1) {
+            Some(remaining) => {
+                let item = entry.item.clone();
+                entry.remaining = remaining;
+                self.shrink_len_items(1);
+                Some((remaining.get(), item))
+            }
+            None => self.take_entry(index).map(|entry| (0, entry.item)),
         };
         if self.is_empty() {
             self.clear()
         }
         taken
     }
+
+    /// Returns a single item of the `element` at `index`
+    /// and the amount of remaining items after this operation.
+    ///
+    /// This is equivalent to [`MultiStash::take_one`] with `Key::from(index)`.
+    pub fn take_one_at(&mut self, index: usize) -> Option<(usize, T)> {
+        self.take_one(Key(index))
+    }
+
+    /// Returns a single item of the `element` at `key`
+    /// and the amount of remaining items after this operation.
+    ///
+    /// Returns `Ok(None)` if `key` refers to a vacant entry or is out of bounds.
+    ///
+    /// # Errors
+    ///
+    /// If the counters of the [`MultiStash`] would underflow in which case it remains unchanged.
+    pub fn try_take_one(&mut self, key: Key) -> Result<Option<(usize, T)>, Corruption> {
+        if let Some(entry) = self.occupied_entry(key) {
+            if self.len_items == 0 || (entry.remaining.get() == 1 && self.len_occupied == 0) {
+                return Err(Corruption::new(key));
+            }
+        }
+        Ok(self.take_one(key))
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: zeroize::Zeroize> MultiStash<T> {
+    /// Clears the [`MultiStash`], zeroizing all elements before dropping them.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the vector.
+    pub fn clear_zeroize(&mut self) {
+        for entry in &mut self.entries {
+            if let Entry::Occupied(entry) = entry {
+                entry.item.zeroize();
+            }
+        }
+        self.clear();
+    }
+
+    /// Removes the `element` at `key`, zeroizing it before dropping it.
+    ///
+    /// Returns the amount of removed items or `None` if `key` refers
+    /// to a vacant entry or is out of bounds.
+    ///
+    /// # Note
+    ///
+    /// The `element` is zeroized in place so that it does not linger in the memory of the
+    /// [`MultiStash`]. Elements removed via [`MultiStash::take_all`] are not zeroized.
+    pub fn take_all_zeroize(&mut self, key: Key) -> Option<usize> {
+        self.occupied_entry_mut(key)?.item.zeroize();
+        self.take_all(key).map(|(remaining, _)| remaining)
+    }
 }
 
 impl<'a, T> IntoIterator for &'a MultiStash<T> {
@@ -397,14 +2111,49 @@ impl<T> Index<Key> for MultiStash<T> {
     }
 }
 
+/// Mutably accesses the element at a [`Key`].
+///
+/// Assigning through the returned reference as in `stash[key] = item` replaces the element
+/// but leaves its remaining items and [`MultiStash::len_items`] unchanged.
+///
+/// # Panics
+///
+/// If `key` refers to a vacant entry or is out of bounds.
 impl<T> IndexMut<Key> for MultiStash<T> {
     fn index_mut(&mut self, key: Key) -> &mut Self::Output {
-        self.get_mut(key)
-            .map(|(_, item)| item)
-            .unwrap_or_else(|| panic!("found no item at index {}", key.0))
+        let len_items = self.len_items;
+        let (remaining, item) = self
+            .get_mut(key)
+            .unwrap_or_else(|| panic!("found no item at index {}", key.0));
+        // Only the item is handed out so the counters stay in sync with the entry.
+        debug_assert!(
+            remaining <= len_items,
+            "remaining items ({remaining}) at {} exceed the total number of items ({len_items})",
+            key.0,
+        );
+        item
     }
 }
 
+impl<T> Index<usize> for MultiStash<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self[Key(index)]
+    }
+}
+
+impl<T> IndexMut<usize> for MultiStash<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self[Key(index)]
+    }
+}
+
+/// Puts all elements in the order yielded by the iterator.
+///
+/// Vacant entries are reused in the reverse order in which they have been freed
+/// before new entries are appended at the end of the [`MultiStash`].
+/// Use [`MultiStash::extend_dense`] to fill vacant entries in ascending key order instead.
 impl<T> Extend<(NonZeroUsize, T)> for MultiStash<T> {
     fn extend<I: IntoIterator<Item = (NonZeroUsize, T)>>(&mut self, iter: I) {
         for (amount, item) in iter {
@@ -415,8 +2164,22 @@ impl<T> Extend<(NonZeroUsize, T)> for MultiStash<T> {
 
 impl<T> FromIterator<(NonZeroUsize, T)> for MultiStash<T> {
     fn from_iter<I: IntoIterator<Item = (NonZeroUsize, T)>>(iter: I) -> Self {
-        let mut stash = Self::new();
+        let iter = iter.into_iter();
+        let mut stash = Self::with_capacity(iter.size_hint().0);
         stash.extend(iter);
         stash
     }
 }
+
+/// Puts all elements with a non-zero amount of items.
+///
+/// # Note
+///
+/// Elements with an amount of zero are silently skipped.
+impl<T> FromIterator<(usize, T)> for MultiStash<T> {
+    fn from_iter<I: IntoIterator<Item = (usize, T)>>(iter: I) -> Self {
+        iter.into_iter()
+            .filter_map(|(amount, item)| Some((NonZeroUsize::new(amount)?, item)))
+            .collect()
+    }
+}
@@ -0,0 +1,273 @@
+use super::{checked_len_items, ensure_max_per_entry, Key, MultiStash};
+use core::cell::Cell;
+use core::num::NonZeroUsize;
+
+/// Meta information about a single slot of a [`MultiStash`].
+///
+/// This struct is created by [`MultiStash::get_with_meta`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SlotMeta<'a, T> {
+    /// The remaining items of the slot or `0` if the slot is vacant.
+    remaining: usize,
+    /// The element stored in the slot if the slot is occupied.
+    item: Option<&'a T>,
+    /// `true` if the slot is the one that the next put reuses.
+    is_next_free_target: bool,
+}
+
+impl<'a, T> SlotMeta<'a, T> {
+    /// Creates a new [`SlotMeta`].
+    pub(crate) fn new(remaining: usize, item: Option<&'a T>, is_next_free_target: bool) -> Self {
+        Self {
+            remaining,
+            item,
+            is_next_free_target,
+        }
+    }
+
+    /// Returns the amount of remaining items of the slot.
+    ///
+    /// Returns `0` if the slot is vacant.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns a reference to the element stored in the slot if it is occupied.
+    pub fn item(&self) -> Option<&'a T> {
+        self.item
+    }
+
+    /// Returns `true` if the slot is occupied.
+    pub fn is_occupied(&self) -> bool {
+        self.item.is_some()
+    }
+
+    /// Returns `true` if the slot is the one that is reused by the next put.
+    ///
+    /// This is only ever `true` for vacant slots.
+    pub fn is_next_free_target(&self) -> bool {
+        self.is_next_free_target
+    }
+}
+
+/// A reference to an element of a [`MultiStash`] and its remaining items.
+///
+/// This struct is created by [`MultiStash::get_ref`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ItemRef<'a, T> {
+    /// The remaining items of the element.
+    remaining: usize,
+    /// The referenced element.
+    item: &'a T,
+}
+
+impl<'a, T> ItemRef<'a, T> {
+    /// Creates a new [`ItemRef`].
+    pub(crate) fn new(remaining: usize, item: &'a T) -> Self {
+        Self { remaining, item }
+    }
+
+    /// Returns the amount of remaining items of the element.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns a reference to the element.
+    pub fn item(&self) -> &'a T {
+        self.item
+    }
+}
+
+/// A mutable reference to an element of a [`MultiStash`] and its remaining items.
+///
+/// This struct is created by [`MultiStash::get_mut_ref`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct ItemMut<'a, T> {
+    /// The remaining items of the element.
+    remaining: usize,
+    /// The referenced element.
+    item: &'a mut T,
+}
+
+impl<'a, T> ItemMut<'a, T> {
+    /// Creates a new [`ItemMut`].
+    pub(crate) fn new(remaining: usize, item: &'a mut T) -> Self {
+        Self { remaining, item }
+    }
+
+    /// Returns the amount of remaining items of the element.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns a reference to the element.
+    pub fn item(&self) -> &T {
+        self.item
+    }
+
+    /// Returns a mutable reference to the element.
+    pub fn item_mut(&mut self) -> &mut T {
+        self.item
+    }
+
+    /// Consumes the [`ItemMut`] and returns the mutable reference to the element.
+    pub fn into_item_mut(self) -> &'a mut T {
+        self.item
+    }
+}
+
+/// A mutable handle to the remaining items of an element of a [`MultiStash`].
+///
+/// Changes to the remaining items are immediately accounted for in [`MultiStash::len_items`].
+///
+/// This struct is created by [`MultiStash::counts_mut`].
+#[derive(Debug)]
+pub struct RemainingMut<'a> {
+    /// The remaining items of the element.
+    remaining: &'a mut NonZeroUsize,
+    /// The number of items of the [`MultiStash`] shared by all handles.
+    len_items: &'a Cell<usize>,
+    /// The maximum amount of items a single element may hold if any.
+    max_per_entry: Option<NonZeroUsize>,
+}
+
+impl<'a> RemainingMut<'a> {
+    /// Creates a new [`RemainingMut`].
+    pub(crate) fn new(
+        remaining: &'a mut NonZeroUsize,
+        len_items: &'a Cell<usize>,
+        max_per_entry: Option<NonZeroUsize>,
+    ) -> Self {
+        Self {
+            remaining,
+            len_items,
+            max_per_entry,
+        }
+    }
+
+    /// Returns the amount of remaining items of the element.
+    pub fn get(&self) -> NonZeroUsize {
+        *self.remaining
+    }
+
+    /// Sets the amount of remaining items of the element to `new_remaining`.
+    ///
+    /// # Panics
+    ///
+    /// - If the number of items in the [`MultiStash`] overflows.
+    /// - If `new_remaining` exceeds the [`MultiStash::max_per_entry`].
+    pub fn set(&mut self, new_remaining: NonZeroUsize) {
+        ensure_max_per_entry(self.max_per_entry, new_remaining.get());
+        let len_items = checked_len_items(
+            self.len_items.get() - self.remaining.get(),
+            new_remaining.get(),
+        );
+        *self.remaining = new_remaining;
+        self.len_items.set(len_items);
+    }
+}
+
+/// The result of removing an element from a [`MultiStash`].
+///
+/// This struct is created by [`MultiStash::take_all_detailed`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TakeResult<T> {
+    /// The remaining items of the removed element.
+    remaining: usize,
+    /// The removed element.
+    item: T,
+    /// The index of the slot that the next put reuses after the removal.
+    became_free_index: usize,
+}
+
+impl<T> TakeResult<T> {
+    /// Creates a new [`TakeResult`].
+    pub(crate) fn new(remaining: usize, item: T, became_free_index: usize) -> Self {
+        Self {
+            remaining,
+            item,
+            became_free_index,
+        }
+    }
+
+    /// Returns the amount of remaining items of the removed element.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns a reference to the removed element.
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+
+    /// Consumes the [`TakeResult`] and returns the removed element.
+    pub fn into_item(self) -> T {
+        self.item
+    }
+
+    /// Returns the index of the slot that the next [`MultiStash::put`] reuses.
+    ///
+    /// This usually is the index of the removed element which became the head of the
+    /// free list. If the removal reclaimed vacant entries at the end of the [`MultiStash`]
+    /// this is the new end of the [`MultiStash`] instead.
+    pub fn became_free_index(&self) -> usize {
+        self.became_free_index
+    }
+}
+
+/// A handle to an element that has just been put into a [`MultiStash`].
+///
+/// This struct is created by [`MultiStash::put_entry`].
+#[derive(Debug)]
+pub struct OccupiedHandle<'a, T> {
+    /// The [`MultiStash`] storing the element.
+    stash: &'a mut MultiStash<T>,
+    /// The key of the element.
+    key: Key,
+}
+
+impl<'a, T> OccupiedHandle<'a, T> {
+    /// Creates a new [`OccupiedHandle`] to the occupied entry at `key`.
+    pub(crate) fn new(stash: &'a mut MultiStash<T>, key: Key) -> Self {
+        Self { stash, key }
+    }
+
+    /// Returns the [`Key`] of the element.
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    /// Returns the amount of remaining items of the element.
+    pub fn remaining(&self) -> usize {
+        self.stash
+            .get(self.key)
+            .map(|(remaining, _)| remaining)
+            .expect("handle refers to an occupied entry")
+    }
+
+    /// Bumps the amount of items of the element by `amount`.
+    ///
+    /// # Panics
+    ///
+    /// See [`MultiStash::bump`].
+    pub fn bump(&mut self, amount: usize) -> &mut Self {
+        self.stash.bump(self.key, amount);
+        self
+    }
+
+    /// Returns a reference to the element.
+    pub fn item(&self) -> &T {
+        self.stash
+            .get(self.key)
+            .map(|(_, item)| item)
+            .expect("handle refers to an occupied entry")
+    }
+
+    /// Returns a mutable reference to the element.
+    pub fn item_mut(&mut self) -> &mut T {
+        self.stash
+            .get_mut(self.key)
+            .map(|(_, item)| item)
+            .expect("handle refers to an occupied entry")
+    }
+}
@@ -0,0 +1,67 @@
+use super::{Entry, Key};
+
+/// A mutable view over a contiguous range of entries of a [`MultiStash`].
+///
+/// Keys used with and yielded by the view are the keys of the underlying [`MultiStash`].
+///
+/// This struct is created by [`MultiStash::split_at_key`].
+///
+/// [`MultiStash`]: crate::MultiStash
+/// [`MultiStash::split_at_key`]: crate::MultiStash::split_at_key
+#[derive(Debug)]
+pub struct SubStashMut<'a, T> {
+    /// The index of the first entry of the view.
+    offset: usize,
+    /// The number of occupied entries of the view.
+    len: usize,
+    /// The entries of the view.
+    entries: &'a mut [Entry<T>],
+}
+
+impl<'a, T> SubStashMut<'a, T> {
+    /// Creates a new [`SubStashMut`] over `entries` starting at `offset`.
+    pub(crate) fn new(offset: usize, len: usize, entries: &'a mut [Entry<T>]) -> Self {
+        Self {
+            offset,
+            len,
+            entries,
+        }
+    }
+
+    /// Returns the number of elements in the view.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the view contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a mutable reference to an element at the `key` if any.
+    ///
+    /// Returns `None` if `key` is outside of the range of the view.
+    pub fn get_mut(&mut self, key: Key) -> Option<(usize, &mut T)> {
+        let index = key.0.checked_sub(self.offset)?;
+        match self.entries.get_mut(index) {
+            Some(Entry::Occupied(entry)) => Some((entry.remaining.get(), &mut entry.item)),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the elements of the view.
+    ///
+    /// The iterator yields all elements, their keys and remaining items from start to end.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Key, usize, &mut T)> {
+        let offset = self.offset;
+        self.entries
+            .iter_mut()
+            .enumerate()
+            .filter_map(move |(index, entry)| match entry {
+                Entry::Occupied(entry) => {
+                    Some((Key(offset + index), entry.remaining.get(), &mut entry.item))
+                }
+                Entry::Vacant(_) => None,
+            })
+    }
+}
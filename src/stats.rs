@@ -0,0 +1,78 @@
+/// Cumulative statistics about the operations performed on a [`MultiStash`].
+///
+/// This struct is accessed via [`MultiStash::stats`].
+///
+/// [`MultiStash`]: crate::MultiStash
+/// [`MultiStash::stats`]: crate::MultiStash::stats
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Stats {
+    /// The number of elements put into the [`MultiStash`](crate::MultiStash).
+    puts: usize,
+    /// The number of puts that reused a vacant entry.
+    reuses: usize,
+    /// The number of puts that appended a new entry.
+    grows: usize,
+    /// The number of elements removed from the [`MultiStash`](crate::MultiStash).
+    takes: usize,
+    /// The number of times the entries have been reallocated.
+    reallocations: usize,
+}
+
+impl Stats {
+    /// Creates new empty [`Stats`].
+    pub(crate) const fn new() -> Self {
+        Self {
+            puts: 0,
+            reuses: 0,
+            grows: 0,
+            takes: 0,
+            reallocations: 0,
+        }
+    }
+
+    /// Records a put that either reused a vacant entry or appended a new entry.
+    pub(crate) fn record_put(&mut self, reused: bool) {
+        self.puts = self.puts.wrapping_add(1);
+        match reused {
+            true => self.reuses = self.reuses.wrapping_add(1),
+            false => self.grows = self.grows.wrapping_add(1),
+        }
+    }
+
+    /// Records the removal of an element.
+    pub(crate) fn record_take(&mut self) {
+        self.takes = self.takes.wrapping_add(1);
+    }
+
+    /// Records a reallocation of the entries if the capacity changed.
+    pub(crate) fn record_capacity(&mut self, old_capacity: usize, new_capacity: usize) {
+        if old_capacity != new_capacity {
+            self.reallocations = self.reallocations.wrapping_add(1);
+        }
+    }
+
+    /// Returns the number of elements put into the [`MultiStash`](crate::MultiStash).
+    pub fn puts(&self) -> usize {
+        self.puts
+    }
+
+    /// Returns the number of puts that reused a vacant entry.
+    pub fn reuses(&self) -> usize {
+        self.reuses
+    }
+
+    /// Returns the number of puts that appended a new entry.
+    pub fn grows(&self) -> usize {
+        self.grows
+    }
+
+    /// Returns the number of elements removed from the [`MultiStash`](crate::MultiStash).
+    pub fn takes(&self) -> usize {
+        self.takes
+    }
+
+    /// Returns the number of times the entries have been reallocated.
+    pub fn reallocations(&self) -> usize {
+        self.reallocations
+    }
+}
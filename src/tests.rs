@@ -1,9 +1,16 @@
 use super::*;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+extern crate std;
 
 fn nz(value: usize) -> NonZeroUsize {
     NonZeroUsize::new(value).unwrap()
 }
 
+// Guards against accidental layout regressions of `Entry`.
+const _: () = assert!(mem::size_of::<Entry<u32>>() == 2 * mem::size_of::<usize>());
+const _: () = assert!(mem::size_of::<Entry<usize>>() == 2 * mem::size_of::<usize>());
+
 #[test]
 fn new_works() {
     let mut stash = <MultiStash<char>>::new();
@@ -144,11 +151,12 @@ fn take_all_but_one_then_refill() {
     assert_eq!(stash.take_all(Key(3)), Some((5, 'D')));
     assert_eq!(stash.take_all(Key(4)), Some((1, 'E')));
 
-    assert_eq!(stash.put(nz(4), 'F'), Key(4));
-    assert_eq!(stash.put(nz(5), 'G'), Key(3));
-    assert_eq!(stash.put(nz(6), 'H'), Key(1));
-    assert_eq!(stash.put(nz(7), 'H'), Key(0));
+    // Taking key(4) reclaimed key(4) and key(3) since they were at the end.
+    assert_eq!(stash.put(nz(4), 'F'), Key(1));
+    assert_eq!(stash.put(nz(5), 'G'), Key(0));
     // Now we fill stash from the back again:
+    assert_eq!(stash.put(nz(6), 'H'), Key(3));
+    assert_eq!(stash.put(nz(7), 'H'), Key(4));
     assert_eq!(stash.put(nz(8), 'I'), Key(5));
 }
 
@@ -209,3 +217,1841 @@ fn bump_fails_2() {
     stash.bump(Key(0), usize::MAX / 2);
     stash.bump(Key(1), usize::MAX / 2);
 }
+
+#[test]
+fn iter_len_interleaved_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([
+        (nz(1), 'A'),
+        (nz(1), 'B'),
+        (nz(1), 'C'),
+        (nz(1), 'D'),
+        (nz(1), 'E'),
+        (nz(1), 'F'),
+        (nz(1), 'G'),
+    ]);
+    // Create a sparse stash with vacant entries at both ends and in between.
+    assert_eq!(stash.take_all(Key(0)), Some((1, 'A')));
+    assert_eq!(stash.take_all(Key(2)), Some((1, 'C')));
+    assert_eq!(stash.take_all(Key(3)), Some((1, 'D')));
+    assert_eq!(stash.take_all(Key(6)), Some((1, 'G')));
+    let mut iter = stash.iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next(), Some((Key(1), 1, &'B')));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.next_back(), Some((Key(5), 1, &'F')));
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next(), Some((Key(4), 1, &'E')));
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.len(), 0);
+    let mut iter = stash.iter_mut();
+    assert_eq!(iter.next_back().map(|(key, _, _)| key), Some(Key(5)));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.next().map(|(key, _, _)| key), Some(Key(1)));
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next_back().map(|(key, _, _)| key), Some(Key(4)));
+    assert_eq!(iter.len(), 0);
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+    assert_eq!(iter.len(), 0);
+    let mut iter = stash.into_iter();
+    assert_eq!(iter.next(), Some((Key(1), 1, 'B')));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.next_back(), Some((Key(5), 1, 'F')));
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next_back(), Some((Key(4), 1, 'E')));
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.len(), 0);
+}
+
+#[test]
+fn extend_after_take_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([
+        (nz(2), 'A'),
+        (nz(3), 'B'),
+        (nz(1), 'C'),
+        (nz(5), 'D'),
+        (nz(1), 'E'),
+    ]);
+    assert_eq!(stash.take_all(Key(0)), Some((2, 'A')));
+    assert_eq!(stash.take_all(Key(1)), Some((3, 'B')));
+    // key(2) not taken!
+    assert_eq!(stash.take_all(Key(3)), Some((5, 'D')));
+    assert_eq!(stash.take_all(Key(4)), Some((1, 'E')));
+
+    // Vacant entries are reused in reverse order of their removal.
+    stash.extend([
+        (nz(4), 'F'),
+        (nz(5), 'G'),
+        (nz(6), 'H'),
+        (nz(7), 'H'),
+        (nz(8), 'I'),
+    ]);
+    assert_eq!(stash.get(Key(1)), Some((4, &'F')));
+    assert_eq!(stash.get(Key(0)), Some((5, &'G')));
+    assert_eq!(stash.get(Key(3)), Some((6, &'H')));
+    assert_eq!(stash.get(Key(4)), Some((7, &'H')));
+    assert_eq!(stash.get(Key(5)), Some((8, &'I')));
+    assert_eq!(stash.len(), 6);
+    assert_eq!(stash.len_items(), 31);
+}
+
+#[test]
+fn extend_dense_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([
+        (nz(2), 'A'),
+        (nz(3), 'B'),
+        (nz(1), 'C'),
+        (nz(5), 'D'),
+        (nz(1), 'E'),
+    ]);
+    assert_eq!(stash.take_all(Key(0)), Some((2, 'A')));
+    assert_eq!(stash.take_all(Key(1)), Some((3, 'B')));
+    // key(2) not taken!
+    assert_eq!(stash.take_all(Key(3)), Some((5, 'D')));
+    assert_eq!(stash.take_all(Key(4)), Some((1, 'E')));
+
+    // Vacant entries are filled in ascending key order before appending.
+    stash.extend_dense([
+        (nz(4), 'F'),
+        (nz(5), 'G'),
+        (nz(6), 'H'),
+        (nz(7), 'H'),
+        (nz(8), 'I'),
+    ]);
+    assert_eq!(stash.get(Key(0)), Some((4, &'F')));
+    assert_eq!(stash.get(Key(1)), Some((5, &'G')));
+    assert_eq!(stash.get(Key(2)), Some((1, &'C')));
+    assert_eq!(stash.get(Key(3)), Some((6, &'H')));
+    assert_eq!(stash.get(Key(4)), Some((7, &'H')));
+    assert_eq!(stash.get(Key(5)), Some((8, &'I')));
+    assert_eq!(stash.len(), 6);
+    assert_eq!(stash.len_items(), 31);
+}
+
+#[test]
+fn extend_dense_partial_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(1), 'A'), (nz(1), 'B'), (nz(1), 'C'), (nz(1), 'D')]);
+    assert_eq!(stash.take_all(Key(2)), Some((1, 'C')));
+    assert_eq!(stash.take_all(Key(0)), Some((1, 'A')));
+    assert_eq!(stash.take_all(Key(1)), Some((1, 'B')));
+    stash.extend_dense([(nz(1), 'E')]);
+    assert_eq!(stash.get(Key(0)), Some((1, &'E')));
+    // Remaining vacant entries are reused in ascending order.
+    assert_eq!(stash.put(nz(1), 'F'), Key(1));
+    assert_eq!(stash.put(nz(1), 'G'), Key(2));
+    assert_eq!(stash.put(nz(1), 'H'), Key(4));
+    assert_eq!(stash.len(), 5);
+    assert_eq!(stash.len_items(), 5);
+}
+
+#[test]
+fn get_with_meta_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B'), (nz(1), 'C')]);
+    assert_eq!(stash.get_with_meta(Key(3)), None);
+    let meta = stash.get_with_meta(Key(1)).unwrap();
+    assert_eq!(meta.remaining(), 3);
+    assert_eq!(meta.item(), Some(&'B'));
+    assert!(meta.is_occupied());
+    assert!(!meta.is_next_free_target());
+    assert_eq!(stash.take_all(Key(0)), Some((2, 'A')));
+    assert_eq!(stash.take_all(Key(1)), Some((3, 'B')));
+    let meta = stash.get_with_meta(Key(1)).unwrap();
+    assert_eq!(meta.remaining(), 0);
+    assert_eq!(meta.item(), None);
+    assert!(!meta.is_occupied());
+    assert!(meta.is_next_free_target());
+    assert!(!stash.get_with_meta(Key(0)).unwrap().is_next_free_target());
+    assert_eq!(stash.put(nz(1), 'D'), Key(1));
+    assert!(stash.get_with_meta(Key(0)).unwrap().is_next_free_target());
+}
+
+#[test]
+fn entries_snapshot_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B'), (nz(1), 'C')]);
+    assert_eq!(stash.take_all(Key(1)), Some((3, 'B')));
+    assert_eq!(
+        stash.entries_snapshot(),
+        [
+            Entry::from(OccupiedEntry::new('A', nz(2))),
+            Entry::from(VacantEntry::new(3)),
+            Entry::from(OccupiedEntry::new('C', nz(1))),
+        ]
+    );
+}
+
+#[test]
+fn index_usize_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B')]);
+    assert_eq!(stash[0], 'A');
+    assert_eq!(stash[1], 'B');
+    stash[1] = 'C';
+    assert_eq!(stash[Key(1)], 'C');
+}
+
+#[test]
+#[should_panic]
+fn index_usize_vacant_fails() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B')]);
+    assert_eq!(stash.take_all(Key(0)), Some((2, 'A')));
+    let _ = stash[0];
+}
+
+#[test]
+#[should_panic]
+fn index_usize_out_of_bounds_fails() {
+    let stash = <MultiStash<char>>::new();
+    let _ = stash[0];
+}
+
+#[test]
+fn take_all_reverse_reclaims() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B'), (nz(1), 'C'), (nz(5), 'D')]);
+    assert_eq!(stash.take_all(Key(3)), Some((5, 'D')));
+    assert_eq!(stash.get_with_meta(Key(3)), None);
+    assert_eq!(stash.take_all(Key(2)), Some((1, 'C')));
+    assert_eq!(stash.get_with_meta(Key(2)), None);
+    assert_eq!(stash.len(), 2);
+    assert_eq!(stash.len_items(), 5);
+    // Keys of the remaining elements are unaffected.
+    assert_eq!(stash.get(Key(0)), Some((2, &'A')));
+    assert_eq!(stash.get(Key(1)), Some((3, &'B')));
+    assert_eq!(stash.put(nz(1), 'E'), Key(2));
+}
+
+#[test]
+fn take_all_reclaims_trailing_vacant() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B'), (nz(1), 'C'), (nz(5), 'D')]);
+    assert_eq!(stash.take_all(Key(2)), Some((1, 'C')));
+    assert!(stash.get_with_meta(Key(2)).is_some());
+    // Taking key(3) also reclaims the vacant key(2).
+    assert_eq!(stash.take_all(Key(3)), Some((5, 'D')));
+    assert_eq!(stash.get_with_meta(Key(2)), None);
+    assert_eq!(stash.len(), 2);
+    assert_eq!(stash.len_items(), 5);
+    assert_eq!(stash.put(nz(1), 'E'), Key(2));
+    assert_eq!(stash.put(nz(1), 'F'), Key(3));
+}
+
+#[test]
+fn take_one_reverse_reclaims() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(1), 'B'), (nz(2), 'C')]);
+    assert_eq!(stash.take_one(Key(2)), Some((1, 'C')));
+    assert!(stash.get_with_meta(Key(2)).is_some());
+    assert_eq!(stash.take_one(Key(2)), Some((0, 'C')));
+    assert_eq!(stash.get_with_meta(Key(2)), None);
+    assert_eq!(stash.take_one(Key(1)), Some((0, 'B')));
+    assert_eq!(stash.get_with_meta(Key(1)), None);
+    assert_eq!(stash.len(), 1);
+    assert_eq!(stash.len_items(), 2);
+    assert_eq!(stash.put(nz(1), 'D'), Key(1));
+}
+
+#[test]
+fn reserve_accounts_for_vacant() {
+    let mut stash = <MultiStash<char>>::with_capacity(4);
+    stash.extend([(nz(1), 'A'), (nz(1), 'B'), (nz(1), 'C'), (nz(1), 'D')]);
+    let capacity = stash.capacity();
+    assert_eq!(stash.take_all(Key(0)), Some((1, 'A')));
+    assert_eq!(stash.take_all(Key(1)), Some((1, 'B')));
+    stash.reserve(2);
+    assert_eq!(stash.capacity(), capacity);
+    stash.reserve_exact(2);
+    assert_eq!(stash.capacity(), capacity);
+    stash.reserve_exact(3);
+    assert!(stash.capacity() >= stash.len() + 3);
+}
+
+#[test]
+fn max_per_entry_works() {
+    let mut stash = MultiStashBuilder::new()
+        .with_max_per_entry(nz(5))
+        .build::<char>();
+    assert_eq!(stash.max_per_entry(), Some(nz(5)));
+    assert_eq!(stash.put(nz(5), 'A'), Key(0));
+    assert_eq!(stash.put(nz(2), 'B'), Key(1));
+    assert_eq!(stash.bump(Key(1), 3), Some(2));
+    assert_eq!(stash.get(Key(1)), Some((5, &'B')));
+    assert_eq!(<MultiStash<char>>::new().max_per_entry(), None);
+}
+
+#[test]
+#[should_panic]
+fn max_per_entry_put_fails() {
+    let mut stash = MultiStashBuilder::new()
+        .with_max_per_entry(nz(5))
+        .build::<char>();
+    stash.put(nz(6), 'A');
+}
+
+#[test]
+#[should_panic]
+fn max_per_entry_bump_fails() {
+    let mut stash = MultiStashBuilder::new()
+        .with_max_per_entry(nz(5))
+        .build::<char>();
+    assert_eq!(stash.put(nz(5), 'A'), Key(0));
+    stash.bump(Key(0), 1);
+}
+
+#[test]
+fn max_per_entry_try_put_and_try_bump() {
+    let mut stash = MultiStashBuilder::new()
+        .with_max_per_entry(nz(5))
+        .build::<char>();
+    assert_eq!(stash.try_put(nz(5), 'A'), Ok(Key(0)));
+    let (error, item) = stash.try_put(nz(6), 'B').unwrap_err();
+    assert_eq!(item, 'B');
+    assert_eq!(error.amount(), 6);
+    assert_eq!(error.max(), nz(5));
+    assert_eq!(stash.try_put(nz(4), 'C'), Ok(Key(1)));
+    assert_eq!(stash.try_bump(Key(1), 1), Ok(Some(4)));
+    let error = stash.try_bump(Key(1), 1).unwrap_err();
+    assert_eq!(error.amount(), 6);
+    assert_eq!(
+        stash.try_bump(Key(0), usize::MAX).unwrap_err().amount(),
+        usize::MAX
+    );
+    assert_eq!(stash.try_bump(Key(2), 100), Ok(None));
+    assert_eq!(stash.len(), 2);
+    assert_eq!(stash.len_items(), 10);
+    assert_eq!(stash.get(Key(1)), Some((5, &'C')));
+    let mut unbounded = <MultiStash<char>>::new();
+    assert_eq!(unbounded.try_put(nz(usize::MAX), 'A'), Ok(Key(0)));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn group_by_key_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([
+        (nz(2), 'A'),
+        (nz(3), 'b'),
+        (nz(1), 'C'),
+        (nz(5), 'd'),
+        (nz(1), 'E'),
+    ]);
+    let groups = stash.group_by_key(|c| c.is_uppercase());
+    assert_eq!(groups.len(), 2);
+    assert_eq!(
+        groups[&true],
+        [(Key(0), 2, &'A'), (Key(2), 1, &'C'), (Key(4), 1, &'E')]
+    );
+    assert_eq!(groups[&false], [(Key(1), 3, &'b'), (Key(3), 5, &'d')]);
+}
+
+#[test]
+fn weighted_fold_works() {
+    let mut stash = <MultiStash<u32>>::new();
+    assert_eq!(
+        stash.weighted_fold(0, |acc, n, value| acc + n as u32 * value),
+        0
+    );
+    stash.extend([(nz(2), 10), (nz(3), 100), (nz(1), 1000)]);
+    assert_eq!(stash.take_all(Key(1)), Some((3, 100)));
+    assert_eq!(
+        stash.weighted_fold(0, |acc, n, value| acc + n as u32 * value),
+        1020
+    );
+}
+
+#[test]
+fn entry_niche_works() {
+    assert!(<Entry<u32>>::is_niche_optimized());
+    assert!(<Entry<usize>>::is_niche_optimized());
+    assert!(<Entry<char>>::is_niche_optimized());
+    // There is no space to store the vacant entry's `next_free` next to the niche.
+    assert!(!<Entry<()>>::is_niche_optimized());
+}
+
+#[test]
+fn range_mut_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([
+        (nz(2), 'A'),
+        (nz(3), 'B'),
+        (nz(1), 'C'),
+        (nz(5), 'D'),
+        (nz(1), 'E'),
+    ]);
+    assert_eq!(stash.take_all(Key(2)), Some((1, 'C')));
+    for (_, _, item) in stash.range_mut(Key(1), Key(4)) {
+        *item = item.to_ascii_lowercase();
+    }
+    assert_eq!(
+        stash.iter().map(|(_, _, item)| *item).collect::<Vec<_>>(),
+        ['A', 'b', 'd', 'E']
+    );
+    assert_eq!(
+        stash
+            .range_mut(Key(3), Key(100))
+            .map(|(key, n, item)| (key, n, *item))
+            .collect::<Vec<_>>(),
+        [(Key(3), 5, 'd'), (Key(4), 1, 'E')]
+    );
+    assert_eq!(stash.range_mut(Key(100), Key(200)).count(), 0);
+    assert_eq!(stash.range_mut(Key(3), Key(1)).count(), 0);
+}
+
+#[test]
+fn next_free_key_works() {
+    let mut stash = <MultiStash<char>>::new();
+    assert_eq!(stash.next_free_key(), Key(0));
+    assert_eq!(stash.put(nz(1), 'A'), Key(0));
+    assert_eq!(stash.next_free_key(), Key(1));
+    stash.extend([(nz(1), 'B'), (nz(1), 'C'), (nz(1), 'D')]);
+    assert_eq!(stash.take_all(Key(1)), Some((1, 'B')));
+    assert_eq!(stash.next_free_key(), Key(1));
+    assert_eq!(stash.put(nz(1), 'E'), Key(1));
+    assert_eq!(stash.take_all(Key(2)), Some((1, 'C')));
+    assert_eq!(stash.take_all(Key(3)), Some((1, 'D')));
+    assert_eq!(stash.next_free_key(), Key(2));
+    assert_eq!(stash.put(nz(1), 'F'), Key(2));
+    assert_eq!(stash.next_free_key(), Key(3));
+}
+
+#[test]
+fn extend_exact_works() {
+    let mut stash = <MultiStash<usize>>::new();
+    stash.extend_exact((0..100).map(|i| (nz(1), i)).collect::<Vec<_>>());
+    let capacity = stash.capacity();
+    assert!(capacity >= 100);
+    assert_eq!(stash.len(), 100);
+    for i in 0..10 {
+        assert_eq!(stash.take_all(Key(i)), Some((1, i)));
+    }
+    // Vacant entries are reused so there is no need to reallocate.
+    stash.extend_exact((0..10).map(|i| (nz(1), i)).collect::<Vec<_>>());
+    assert_eq!(stash.capacity(), capacity);
+    assert_eq!(stash.len(), 100);
+}
+
+#[test]
+fn try_take_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B')]);
+    assert_eq!(stash.try_take_one(Key(0)), Ok(Some((1, 'A'))));
+    assert_eq!(stash.try_take_all(Key(1)), Ok(Some((3, 'B'))));
+    assert_eq!(stash.try_take_all(Key(1)), Ok(None));
+    assert_eq!(stash.try_take_one(Key(5)), Ok(None));
+}
+
+#[test]
+fn try_take_corrupted_fails() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B')]);
+    // Simulate corrupted counters.
+    stash.len_items = 2;
+    assert_eq!(stash.try_take_all(Key(1)), Err(Corruption::new(Key(1))));
+    assert_eq!(stash.get(Key(1)), Some((3, &'B')));
+    stash.len_items = 0;
+    assert_eq!(stash.try_take_one(Key(0)), Err(Corruption::new(Key(0))));
+    assert_eq!(stash.get(Key(0)), Some((2, &'A')));
+}
+
+#[test]
+fn min_max_key_works() {
+    let mut stash = <MultiStash<char>>::new();
+    assert_eq!(stash.min_key(), None);
+    assert_eq!(stash.max_key(), None);
+    stash.extend([
+        (nz(2), 'A'),
+        (nz(3), 'B'),
+        (nz(1), 'C'),
+        (nz(5), 'D'),
+        (nz(1), 'E'),
+    ]);
+    assert_eq!(stash.min_key(), Some(Key(0)));
+    assert_eq!(stash.max_key(), Some(Key(4)));
+    assert_eq!(stash.take_all(Key(0)), Some((2, 'A')));
+    assert_eq!(stash.take_all(Key(1)), Some((3, 'B')));
+    assert_eq!(stash.min_key(), Some(Key(2)));
+    assert_eq!(stash.take_all(Key(3)), Some((5, 'D')));
+    assert_eq!(stash.max_key(), Some(Key(4)));
+    assert_eq!(stash.take_one(Key(4)), Some((0, 'E')));
+    assert_eq!(stash.min_key(), Some(Key(2)));
+    assert_eq!(stash.max_key(), Some(Key(2)));
+    assert_eq!(stash.put(nz(1), 'F'), Key(1));
+    assert_eq!(stash.min_key(), Some(Key(1)));
+    assert_eq!(stash.max_key(), Some(Key(2)));
+    assert_eq!(stash.take_all(Key(1)), Some((1, 'F')));
+    assert_eq!(stash.take_all(Key(2)), Some((1, 'C')));
+    assert_eq!(stash.min_key(), None);
+    assert_eq!(stash.max_key(), None);
+}
+
+#[test]
+fn into_stack_iter_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B'), (nz(1), 'C')]);
+    assert_eq!(stash.take_all(Key(1)), Some((3, 'B')));
+    let iter = stash.into_stack_iter();
+    assert_eq!(iter.len(), 2);
+    assert_eq!(
+        iter.collect::<Vec<_>>(),
+        [(Key(2), 1, 'C'), (Key(0), 2, 'A')]
+    );
+}
+
+#[test]
+#[cfg(feature = "stats")]
+fn stats_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B'), (nz(1), 'C')]);
+    assert_eq!(stash.take_all(Key(0)), Some((2, 'A')));
+    assert_eq!(stash.take_one(Key(1)), Some((2, 'B')));
+    assert_eq!(stash.take_all(Key(5)), None);
+    assert_eq!(stash.put(nz(1), 'D'), Key(0));
+    let stats = stash.stats();
+    assert_eq!(stats.puts(), 4);
+    assert_eq!(stats.reuses(), 1);
+    assert_eq!(stats.grows(), 3);
+    assert_eq!(stats.takes(), 1);
+    assert!(stats.reallocations() >= 1);
+    let reallocations = stats.reallocations();
+    stash.reserve(100);
+    assert_eq!(stash.stats().reallocations(), reallocations + 1);
+}
+
+#[test]
+fn free_list_head_reset_after_pop() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let hash = |stash: &MultiStash<char>| {
+        let mut hasher = DefaultHasher::new();
+        stash.hash(&mut hasher);
+        hasher.finish()
+    };
+    let mut stash = <MultiStash<char>>::new();
+    for item in ['a', 'b', 'c', 'd'] {
+        stash.put(nz(1), item);
+    }
+    assert!(stash.take_all(Key(1)).is_some());
+    assert!(stash.take_all(Key(3)).is_some());
+    assert!(stash.take_all(Key(2)).is_some());
+    let mut fresh = <MultiStash<char>>::new();
+    fresh.put(nz(1), 'a');
+    assert_eq!(stash.free, stash.len_entries());
+    assert_eq!(stash, fresh);
+    assert_eq!(hash(&stash), hash(&fresh));
+    assert_eq!(stash.into_parts().1, fresh.into_parts().1);
+    // Reusing vacant entries must not resurrect a stale free list head.
+    let mut stash = <MultiStash<char>>::new();
+    for item in ['a', 'b', 'c', 'd'] {
+        stash.put(nz(1), item);
+    }
+    assert!(stash.take_all(Key(2)).is_some());
+    assert!(stash.take_all(Key(1)).is_some());
+    assert!(stash.take_all(Key(3)).is_some());
+    assert_eq!(stash.put(nz(1), 'b'), Key(1));
+    assert_eq!(stash.put(nz(1), 'c'), Key(2));
+    let mut fresh = <MultiStash<char>>::new();
+    fresh.extend([(nz(1), 'a'), (nz(1), 'b'), (nz(1), 'c')]);
+    assert_eq!(stash, fresh);
+    assert_eq!(hash(&stash), hash(&fresh));
+    assert!(stash.check_invariants());
+}
+
+#[test]
+#[cfg(feature = "stats")]
+fn stats_ignored_by_comparisons() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let hash = |stash: &MultiStash<char>| {
+        let mut hasher = DefaultHasher::new();
+        stash.hash(&mut hasher);
+        hasher.finish()
+    };
+    let mut stash = <MultiStash<char>>::new();
+    stash.put(nz(1), 'A');
+    assert!(stash.take_all(Key(0)).is_some());
+    let empty = <MultiStash<char>>::new();
+    assert_ne!(stash.stats(), empty.stats());
+    assert_eq!(stash, empty);
+    assert_eq!(stash.cmp(&empty), core::cmp::Ordering::Equal);
+    assert_eq!(hash(&stash), hash(&empty));
+}
+
+#[test]
+#[cfg(feature = "stats")]
+fn stats_insert_paths() {
+    let mut stash = <MultiStash<char>>::new();
+    assert_eq!(stash.insert_at(2, nz(1), 'A'), None);
+    assert_eq!(stash.swap_take(Key(0), nz(1), 'B'), None);
+    assert_eq!(stash.swap_take(Key(0), nz(1), 'C'), Some((1, 'B')));
+    let stats = *stash.stats();
+    assert_eq!(stats.puts(), 3);
+    assert_eq!(stats.grows(), 1);
+    assert_eq!(stats.reuses(), 2);
+    assert_eq!(stats.takes(), 1);
+    assert_eq!(stats.reallocations(), 1);
+    let mut other = <MultiStash<char>>::new();
+    other.insert_at(1, nz(1), 'D');
+    other.insert_at(5, nz(1), 'E');
+    stash.merge_with(other, |_, _, _, _| {});
+    let stats = *stash.stats();
+    assert_eq!(stats.puts(), 5);
+    assert_eq!(stats.grows(), 2);
+    assert_eq!(stats.reuses(), 3);
+    assert_eq!(stats.reallocations(), 2);
+}
+
+/// A secret that records its value when dropped to observe whether it was zeroized.
+#[cfg(feature = "zeroize")]
+struct Secret {
+    value: u32,
+    dropped: std::rc::Rc<core::cell::RefCell<Vec<u32>>>,
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Secret {
+    fn zeroize(&mut self) {
+        self.value = 0;
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.dropped.borrow_mut().push(self.value);
+    }
+}
+
+#[test]
+#[cfg(feature = "zeroize")]
+fn zeroize_works() {
+    let dropped = std::rc::Rc::default();
+    let secret = |value| Secret {
+        value,
+        dropped: std::rc::Rc::clone(&dropped),
+    };
+    let mut stash = <MultiStash<Secret>>::new();
+    stash.extend([(nz(2), secret(1)), (nz(3), secret(2)), (nz(1), secret(3))]);
+    assert_eq!(stash.take_all_zeroize(Key(1)), Some(3));
+    assert_eq!(*dropped.borrow(), [0]);
+    assert_eq!(stash.take_all_zeroize(Key(1)), None);
+    assert_eq!(stash.len(), 2);
+    assert_eq!(stash.len_items(), 3);
+    stash.clear_zeroize();
+    assert!(stash.is_empty());
+    assert_eq!(stash.len_items(), 0);
+    assert_eq!(*dropped.borrow(), [0, 0, 0]);
+    // Elements removed without zeroizing keep their value.
+    stash.put(nz(1), secret(4));
+    drop(stash.take_all(Key(0)));
+    assert_eq!(*dropped.borrow(), [0, 0, 0, 4]);
+}
+
+#[test]
+fn split_retain_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([
+        (nz(2), 'A'),
+        (nz(3), 'b'),
+        (nz(1), 'C'),
+        (nz(5), 'd'),
+        (nz(1), 'e'),
+    ]);
+    let removed = stash.split_retain(|_, _, c| c.is_uppercase());
+    assert_eq!(
+        stash.iter().collect::<Vec<_>>(),
+        [(Key(0), 2, &'A'), (Key(2), 1, &'C')]
+    );
+    assert_eq!(stash.len_items(), 3);
+    assert_eq!(
+        removed.iter().collect::<Vec<_>>(),
+        [(Key(0), 3, &'b'), (Key(1), 5, &'d'), (Key(2), 1, &'e')]
+    );
+    assert_eq!(removed.len_items(), 9);
+    let removed = stash.split_retain(|_, _, _| false);
+    assert!(stash.is_empty());
+    assert_eq!(removed.len(), 2);
+    assert_eq!(stash.put(nz(1), 'F'), Key(0));
+}
+
+#[test]
+fn get_ref_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B')]);
+    let item = stash.get_ref(Key(1)).unwrap();
+    assert_eq!(item.remaining(), 3);
+    assert_eq!(item.item(), &'B');
+    assert_eq!(stash.get_ref(Key(2)), None);
+    let mut item = stash.get_mut_ref(Key(0)).unwrap();
+    assert_eq!(item.remaining(), 2);
+    *item.item_mut() = 'C';
+    assert_eq!(item.item(), &'C');
+    assert_eq!(stash.get(Key(0)), Some((2, &'C')));
+    assert!(stash.get_mut_ref(Key(2)).is_none());
+}
+
+#[test]
+fn from_iter_usize_works() {
+    let stash: MultiStash<char> = [(2, 'A'), (0, 'B'), (1, 'C'), (0, 'D')]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        stash.iter().collect::<Vec<_>>(),
+        [(Key(0), 2, &'A'), (Key(1), 1, &'C')]
+    );
+    assert_eq!(stash.len_items(), 3);
+}
+
+#[test]
+fn take_where_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([
+        (nz(2), 'A'),
+        (nz(3), 'b'),
+        (nz(1), 'C'),
+        (nz(5), 'd'),
+        (nz(1), 'E'),
+    ]);
+    assert_eq!(
+        stash.take_where(|_, _, c| c.is_lowercase()),
+        [(Key(1), 3, 'b'), (Key(3), 5, 'd')]
+    );
+    assert_eq!(stash.len(), 3);
+    assert_eq!(stash.len_items(), 4);
+    assert_eq!(stash.get(Key(4)), Some((1, &'E')));
+    assert_eq!(
+        stash.take_where(|_, n, _| n == 1),
+        [(Key(2), 1, 'C'), (Key(4), 1, 'E')]
+    );
+    assert_eq!(stash.take_where(|_, _, _| true), [(Key(0), 2, 'A')]);
+    assert!(stash.is_empty());
+    assert_eq!(stash.put(nz(1), 'F'), Key(0));
+}
+
+#[test]
+fn const_new_works() {
+    const EMPTY: MultiStash<char> = MultiStash::new();
+    static STASH: MultiStash<char> = MultiStash::new();
+    assert!(EMPTY.is_empty());
+    assert!(STASH.is_empty());
+    assert_eq!(STASH.capacity(), 0);
+}
+
+#[test]
+fn truncate_vacant_tail_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([
+        (nz(2), 'A'),
+        (nz(3), 'B'),
+        (nz(1), 'C'),
+        (nz(5), 'D'),
+        (nz(1), 'E'),
+    ]);
+    assert_eq!(stash.truncate_vacant_tail(), 0);
+    assert_eq!(stash.take_all(Key(3)), Some((5, 'D')));
+    assert_eq!(stash.take_all(Key(1)), Some((3, 'B')));
+    assert_eq!(stash.take_all(Key(4)), Some((1, 'E')));
+    // key(3) is not at the head of the free list and thus not yet reclaimed.
+    assert!(stash.get_with_meta(Key(3)).is_some());
+    let capacity = stash.capacity();
+    assert_eq!(stash.truncate_vacant_tail(), 1);
+    assert_eq!(stash.capacity(), capacity);
+    assert_eq!(stash.get_with_meta(Key(3)), None);
+    assert_eq!(stash.len(), 2);
+    assert_eq!(stash.len_items(), 3);
+    assert_eq!(stash.put(nz(1), 'F'), Key(1));
+    assert_eq!(stash.put(nz(1), 'G'), Key(3));
+}
+
+#[test]
+fn occupancy_bitmap_works() {
+    let mut stash = <MultiStash<usize>>::new();
+    assert!(stash.occupancy_bitmap().is_empty());
+    stash.extend((0..70).map(|i| (nz(1), i)));
+    for i in (0..70).filter(|i| i % 3 != 0 && *i != 69) {
+        assert_eq!(stash.take_all(Key(i)), Some((1, i)));
+    }
+    let bitmap = stash.occupancy_bitmap();
+    assert_eq!(bitmap.len(), 2);
+    for i in 0..70 {
+        let is_set = bitmap[i / 64] & (1 << (i % 64)) != 0;
+        assert_eq!(is_set, stash.get(Key(i)).is_some());
+    }
+}
+
+#[test]
+fn take_bitmap_works() {
+    let mut stash = <MultiStash<usize>>::new();
+    stash.extend((0..70).map(|i| (nz(i + 1), i)));
+    assert_eq!(stash.take_all(Key(2)), Some((3, 2)));
+    // Bit 2 refers to a vacant entry and bits beyond 70 are out of bounds.
+    let bitmap = [0b1101, 1 << 5 | 1 << 6 | 1 << 63, u64::MAX];
+    assert_eq!(
+        stash.take_bitmap(&bitmap),
+        [(Key(0), 1, 0), (Key(3), 4, 3), (Key(69), 70, 69)]
+    );
+    assert_eq!(stash.len(), 66);
+    assert!(stash.get(Key(68)).is_some());
+    assert!(stash.get(Key(69)).is_none());
+    // A shorter bitmap leaves all other entries untouched.
+    assert_eq!(stash.take_bitmap(&[0b10]), [(Key(1), 2, 1)]);
+    assert_eq!(stash.len(), 65);
+    assert_eq!(stash.take_bitmap(&[u64::MAX; 2]).len(), 65);
+    assert!(stash.is_empty());
+}
+
+#[test]
+fn put_panic_leaves_stash_unchanged() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(usize::MAX - 1), 'A'), (nz(1), 'B')]);
+    assert_eq!(stash.take_all(Key(0)), Some((usize::MAX - 1, 'A')));
+    let before = stash.clone();
+    // Reusing the vacant entry at key(0) overflows the number of items.
+    let result = catch_unwind(AssertUnwindSafe(|| stash.put(nz(usize::MAX), 'C')));
+    assert!(result.is_err());
+    assert_eq!(stash, before);
+    assert_eq!(stash.put(nz(1), 'D'), Key(0));
+    let before = stash.clone();
+    // Pushing a new entry overflows the number of items.
+    let result = catch_unwind(AssertUnwindSafe(|| stash.put(nz(usize::MAX - 1), 'E')));
+    assert!(result.is_err());
+    assert_eq!(stash, before);
+}
+
+#[test]
+fn bump_panic_leaves_stash_unchanged() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(usize::MAX - 1), 'A'), (nz(1), 'B')]);
+    let before = stash.clone();
+    let result = catch_unwind(AssertUnwindSafe(|| stash.bump(Key(1), 1)));
+    assert!(result.is_err());
+    assert_eq!(stash, before);
+}
+
+#[test]
+fn take_one_panic_leaves_stash_unchanged() {
+    #[derive(Debug, PartialEq, Eq)]
+    struct PanicOnClone;
+
+    impl Clone for PanicOnClone {
+        fn clone(&self) -> Self {
+            panic!("cannot clone PanicOnClone")
+        }
+    }
+
+    let mut stash = <MultiStash<PanicOnClone>>::new();
+    stash.put(nz(1), PanicOnClone);
+    stash.put(nz(2), PanicOnClone);
+    let result = catch_unwind(AssertUnwindSafe(|| stash.take_one(Key(1))));
+    assert!(result.is_err());
+    assert_eq!(stash.len(), 2);
+    assert_eq!(stash.len_items(), 3);
+    assert_eq!(stash.get(Key(1)), Some((2, &PanicOnClone)));
+    // Removing the last item does not require a clone.
+    assert_eq!(stash.take_one(Key(0)), Some((0, PanicOnClone)));
+}
+
+#[test]
+fn collect_into_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B'), (nz(1), 'C')]);
+    assert_eq!(stash.take_all(Key(1)), Some((3, 'B')));
+    assert_eq!(stash.iter().size_hint(), (2, Some(2)));
+    let mut target = vec![(Key(42), 1, 'Z')];
+    stash.collect_into(&mut target);
+    assert_eq!(
+        target,
+        [(Key(42), 1, 'Z'), (Key(0), 2, 'A'), (Key(2), 1, 'C')]
+    );
+}
+
+#[test]
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+fn indexed_by_item_works() {
+    let mut stash = <IndexedByItem<char>>::new();
+    assert_eq!(stash.key_of(&'A'), None);
+    assert_eq!(stash.put(nz(2), 'A'), Key(0));
+    assert_eq!(stash.put(nz(3), 'B'), Key(1));
+    assert_eq!(stash.put(nz(1), 'A'), Key(0));
+    assert_eq!(stash.len(), 2);
+    assert_eq!(stash.len_items(), 6);
+    assert_eq!(stash.key_of(&'A'), Some(Key(0)));
+    assert_eq!(stash.key_of(&'B'), Some(Key(1)));
+    assert_eq!(stash.replace(Key(1), 'C'), Some('B'));
+    assert_eq!(stash.key_of(&'B'), None);
+    assert_eq!(stash.key_of(&'C'), Some(Key(1)));
+    assert_eq!(stash.get(Key(1)), Some((3, &'C')));
+    assert_eq!(stash.take_one(Key(0)), Some((2, 'A')));
+    assert_eq!(stash.key_of(&'A'), Some(Key(0)));
+    assert_eq!(stash.take_all(Key(0)), Some((2, 'A')));
+    assert_eq!(stash.key_of(&'A'), None);
+    assert_eq!(stash.take_one(Key(0)), None);
+    assert_eq!(stash.replace(Key(0), 'D'), None);
+    assert_eq!(stash.key_of(&'D'), None);
+}
+
+#[test]
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+#[should_panic]
+fn indexed_by_item_replace_duplicate_fails() {
+    let mut stash = <IndexedByItem<char>>::new();
+    assert_eq!(stash.put(nz(2), 'A'), Key(0));
+    assert_eq!(stash.put(nz(3), 'B'), Key(1));
+    stash.replace(Key(1), 'A');
+}
+
+#[test]
+fn insert_at_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([
+        (nz(2), 'A'),
+        (nz(3), 'B'),
+        (nz(1), 'C'),
+        (nz(5), 'D'),
+        (nz(1), 'E'),
+    ]);
+    // Occupied slot.
+    assert_eq!(stash.insert_at(1, nz(4), 'F'), Some((3, 'B')));
+    assert_eq!(stash.get(Key(1)), Some((4, &'F')));
+    assert_eq!(stash.len(), 5);
+    assert_eq!(stash.len_items(), 13);
+    // Vacant slot in the middle of the free list.
+    assert_eq!(stash.take_all(Key(0)), Some((2, 'A')));
+    assert_eq!(stash.take_all(Key(2)), Some((1, 'C')));
+    assert_eq!(stash.take_all(Key(3)), Some((5, 'D')));
+    assert_eq!(stash.insert_at(2, nz(1), 'G'), None);
+    assert_eq!(stash.get(Key(2)), Some((1, &'G')));
+    assert_eq!(stash.len(), 3);
+    assert_eq!(stash.len_items(), 6);
+    assert_eq!(stash.put(nz(1), 'H'), Key(3));
+    assert_eq!(stash.put(nz(1), 'I'), Key(0));
+    assert_eq!(stash.put(nz(1), 'J'), Key(5));
+    // Out of bounds slot.
+    assert_eq!(stash.insert_at(8, nz(2), 'K'), None);
+    assert_eq!(stash.get(Key(8)), Some((2, &'K')));
+    assert_eq!(stash.len(), 7);
+    assert_eq!(stash.len_items(), 11);
+    assert_eq!(stash.max_key(), Some(Key(8)));
+    assert_eq!(stash.put(nz(1), 'L'), Key(6));
+    assert_eq!(stash.put(nz(1), 'M'), Key(7));
+    assert_eq!(stash.put(nz(1), 'N'), Key(9));
+}
+
+#[test]
+fn insert_at_with_free_list_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(1), 'A'), (nz(1), 'B'), (nz(1), 'C')]);
+    assert_eq!(stash.take_all(Key(1)), Some((1, 'B')));
+    // The free list is non-empty while growing the stash.
+    assert_eq!(stash.insert_at(3, nz(1), 'D'), None);
+    assert_eq!(stash.put(nz(1), 'E'), Key(1));
+    assert_eq!(stash.put(nz(1), 'F'), Key(4));
+    assert_eq!(stash.insert_at(5, nz(1), 'G'), None);
+    assert_eq!(stash.put(nz(1), 'H'), Key(6));
+    assert_eq!(stash.len(), 7);
+    assert_eq!(stash.len_items(), 7);
+}
+
+#[test]
+fn counts_mut_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B'), (nz(5), 'C')]);
+    assert_eq!(stash.take_all(Key(1)), Some((3, 'B')));
+    let mut handles = stash.counts_mut().collect::<Vec<_>>();
+    assert_eq!(handles[0].1.get(), nz(2));
+    assert_eq!(handles[1].2, &'C');
+    handles[0].1.set(nz(10));
+    handles[1].1.set(nz(1));
+    drop(handles);
+    assert_eq!(stash.get(Key(0)), Some((10, &'A')));
+    assert_eq!(stash.get(Key(2)), Some((1, &'C')));
+    assert_eq!(stash.len_items(), 11);
+    for (_, mut remaining, item) in stash.counts_mut() {
+        if *item == 'A' {
+            remaining.set(nz(remaining.get().get() / 2));
+        }
+    }
+    assert_eq!(stash.len_items(), 6);
+}
+
+#[test]
+#[should_panic]
+fn counts_mut_max_per_entry_fails() {
+    let mut stash = MultiStashBuilder::new()
+        .with_max_per_entry(nz(5))
+        .build::<char>();
+    stash.put(nz(2), 'A');
+    for (_, mut remaining, _) in stash.counts_mut() {
+        remaining.set(nz(6));
+    }
+}
+
+#[test]
+fn compact_is_stable() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABCDEFGH".chars().map(|c| (nz(1), c)));
+    for index in [0, 2, 3, 6] {
+        assert!(stash.take_all(Key(index)).is_some());
+    }
+    let before = stash.iter().map(|(_, _, item)| *item).collect::<Vec<_>>();
+    assert_eq!(
+        stash.compact(),
+        [
+            (Key(1), Key(0)),
+            (Key(4), Key(1)),
+            (Key(5), Key(2)),
+            (Key(7), Key(3)),
+        ]
+    );
+    let after = stash.iter().map(|(_, _, item)| *item).collect::<Vec<_>>();
+    assert_eq!(before, after);
+    assert_eq!(after, ['B', 'E', 'F', 'H']);
+    assert_eq!(stash.len(), 4);
+    assert_eq!(stash.len_items(), 4);
+    assert_eq!(stash.min_key(), Some(Key(0)));
+    assert_eq!(stash.max_key(), Some(Key(3)));
+    assert!(stash.compact().is_empty());
+    assert_eq!(stash.put(nz(1), 'I'), Key(4));
+}
+
+#[test]
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+fn counting_stash_works() {
+    let mut stash = <CountingStash<&str>>::new();
+    let a = stash.add("a");
+    let b = stash.add("b");
+    assert_eq!(stash.add("a"), a);
+    assert_eq!(stash.add("a"), a);
+    assert_eq!(stash.len(), 2);
+    assert_eq!(stash.len_items(), 4);
+    assert_eq!(stash.count_of(&"a"), 3);
+    assert_eq!(stash.get(b), Some((1, &"b")));
+    assert_eq!(stash.remove(&"a"), Some(2));
+    assert_eq!(stash.remove(&"b"), Some(0));
+    assert_eq!(stash.remove(&"b"), None);
+    assert_eq!(stash.key_of(&"b"), None);
+    assert_eq!(stash.count_of(&"b"), 0);
+    assert_eq!(stash.add("c"), b);
+    assert_eq!(stash.remove(&"a"), Some(1));
+    assert_eq!(stash.remove(&"a"), Some(0));
+    assert_eq!(stash.remove(&"c"), Some(0));
+    assert!(stash.is_empty());
+}
+
+#[test]
+fn iter_count_and_last_work() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABCDE".chars().map(|c| (nz(1), c)));
+    assert!(stash.take_all(Key(1)).is_some());
+    assert!(stash.take_all(Key(4)).is_some());
+    assert_eq!(stash.iter().count(), stash.len());
+    assert_eq!(stash.iter_mut().count(), stash.len());
+    assert_eq!(stash.clone().into_iter().count(), stash.len());
+    let mut iter = stash.iter();
+    assert_eq!(iter.next(), Some((Key(0), 1, &'A')));
+    assert_eq!(iter.count(), 2);
+    assert_eq!(stash.iter().last(), Some((Key(3), 1, &'D')));
+    assert_eq!(stash.iter_mut().last(), Some((Key(3), 1, &mut 'D')));
+    assert_eq!(stash.clone().into_iter().last(), Some((Key(3), 1, 'D')));
+    assert_eq!(<MultiStash<char>>::new().iter().last(), None);
+}
+
+#[test]
+fn iter_count_does_not_traverse() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABC".chars().map(|c| (nz(1), c)));
+    // A sentinel that differs from the actual number of occupied entries
+    // so that only a non-traversing `count` can return it.
+    let sentinel = 1000;
+    stash.len_occupied = sentinel;
+    assert_eq!(stash.iter().count(), sentinel);
+    assert_eq!(stash.iter_mut().count(), sentinel);
+    assert_eq!(stash.into_iter().count(), sentinel);
+}
+
+#[test]
+fn key_at_rank_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABCDE".chars().map(|c| (nz(1), c)));
+    assert!(stash.take_all(Key(0)).is_some());
+    assert!(stash.take_all(Key(2)).is_some());
+    assert_eq!(stash.key_at_rank(0), Some(Key(1)));
+    assert_eq!(stash.key_at_rank(1), Some(Key(3)));
+    assert_eq!(stash.key_at_rank(2), Some(Key(4)));
+    assert_eq!(stash.key_at_rank(3), None);
+    assert_eq!(<MultiStash<char>>::new().key_at_rank(0), None);
+}
+
+#[test]
+fn merge_with_works() {
+    let sum = |_: &mut char, _: char, remaining: &mut NonZeroUsize, other: NonZeroUsize| {
+        *remaining = remaining.checked_add(other.get()).unwrap();
+    };
+    // Overlapping keys sum their amounts.
+    let mut a = <MultiStash<char>>::new();
+    a.extend([(nz(1), 'A'), (nz(2), 'B')]);
+    let mut b = <MultiStash<char>>::new();
+    b.extend([(nz(3), 'A'), (nz(4), 'B')]);
+    a.merge_with(b, sum);
+    assert_eq!(a.get(Key(0)), Some((4, &'A')));
+    assert_eq!(a.get(Key(1)), Some((6, &'B')));
+    assert_eq!(a.len(), 2);
+    assert_eq!(a.len_items(), 10);
+    // Disjoint keys are inserted at their original key.
+    let mut a = <MultiStash<char>>::new();
+    a.extend([(nz(1), 'A'), (nz(1), 'B')]);
+    assert!(a.take_all(Key(1)).is_some());
+    let mut b = <MultiStash<char>>::new();
+    b.extend([(nz(2), 'X'), (nz(2), 'B'), (nz(2), 'X'), (nz(2), 'D')]);
+    assert!(b.take_all(Key(0)).is_some());
+    assert!(b.take_all(Key(2)).is_some());
+    a.merge_with(b, sum);
+    assert_eq!(a.get(Key(0)), Some((1, &'A')));
+    assert_eq!(a.get(Key(1)), Some((2, &'B')));
+    assert_eq!(a.get(Key(2)), None);
+    assert_eq!(a.get(Key(3)), Some((2, &'D')));
+    assert_eq!(a.len(), 3);
+    assert_eq!(a.len_items(), 5);
+    assert_eq!(a.max_key(), Some(Key(3)));
+    assert_eq!(a.put(nz(1), 'C'), Key(2));
+    assert_eq!(a.put(nz(1), 'E'), Key(4));
+    // Conflicting elements are reconciled by the combinator.
+    let mut a = <MultiStash<char>>::new();
+    a.extend([(nz(5), 'A'), (nz(1), 'B')]);
+    let mut b = <MultiStash<char>>::new();
+    b.extend([(nz(2), 'Z'), (nz(3), 'Y')]);
+    a.merge_with(b, |item, other, remaining, other_remaining| {
+        if other_remaining > *remaining {
+            *item = other;
+            *remaining = other_remaining;
+        }
+    });
+    assert_eq!(a.get(Key(0)), Some((5, &'A')));
+    assert_eq!(a.get(Key(1)), Some((3, &'Y')));
+    assert_eq!(a.len_items(), 8);
+    assert!(a.check_invariants());
+}
+
+#[test]
+fn merge_with_interleaved_holes() {
+    let mut a = (0..1000).map(|n| (nz(1), n)).collect::<MultiStash<u32>>();
+    let mut b = (0..2000).map(|n| (nz(2), n)).collect::<MultiStash<u32>>();
+    a.take_where(|key, _, _| key.0 % 3 == 0);
+    b.take_where(|key, _, _| key.0 % 2 == 0 || key.0 >= 1500);
+    let len_items = a.len_items() + b.len_items();
+    a.merge_with(b, |_, _, remaining, other| {
+        *remaining = remaining.checked_add(other.get()).unwrap();
+    });
+    assert!(a.check_invariants());
+    assert_eq!(a.len_items(), len_items);
+    assert_eq!(a.max_key(), Some(Key(1499)));
+    assert_eq!(a.get(Key(0)), None);
+    assert_eq!(a.get(Key(1)), Some((3, &1)));
+    assert_eq!(a.get(Key(3)), Some((2, &3)));
+    assert_eq!(a.next_free_key(), Key(0));
+}
+
+#[test]
+fn try_for_each_works() {
+    let mut stash = <MultiStash<u32>>::new();
+    stash.extend([(nz(1), 10), (nz(2), 20), (nz(3), 30), (nz(4), 40)]);
+    assert!(stash.take_all(Key(0)).is_some());
+    let mut visited = Vec::new();
+    let result = stash.try_for_each(|key, remaining, item| {
+        visited.push(key);
+        if remaining as u32 * item > 50 {
+            return Err(key);
+        }
+        Ok(())
+    });
+    assert_eq!(result, Err(Key(2)));
+    assert_eq!(visited, [Key(1), Key(2)]);
+    let result = stash.try_for_each_mut(|_, remaining, item| {
+        *item += remaining as u32;
+        Ok::<(), ()>(())
+    });
+    assert_eq!(result, Ok(()));
+    assert_eq!(stash.get(Key(1)), Some((2, &22)));
+    assert_eq!(stash.get(Key(3)), Some((4, &44)));
+    assert_eq!(
+        <MultiStash<u32>>::new().try_for_each(|_, _, _| Err(())),
+        Ok(())
+    );
+}
+
+#[test]
+fn multi_stash_clone_from_works() {
+    let mut source = <MultiStash<char>>::new();
+    source.extend("ABC".chars().map(|c| (nz(2), c)));
+    assert!(source.take_all(Key(1)).is_some());
+    let mut target = <MultiStash<char>>::with_capacity(16);
+    target.extend("XYZW".chars().map(|c| (nz(1), c)));
+    let capacity = target.capacity();
+    target.clone_from(&source);
+    assert_eq!(target, source);
+    assert_eq!(target.capacity(), capacity);
+    assert_eq!(target.put(nz(1), 'D'), source.clone().put(nz(1), 'D'));
+}
+
+#[test]
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+fn indexed_by_item_clone_from_works() {
+    let mut original = <IndexedByItem<char>>::new();
+    let a = original.put(nz(1), 'A');
+    let b = original.put(nz(2), 'B');
+    let mut clone = <IndexedByItem<char>>::new();
+    clone.put(nz(1), 'X');
+    clone.clone_from(&original);
+    assert_eq!(clone.take_all(a), Some((1, 'A')));
+    assert_eq!(clone.put(nz(1), 'C'), a);
+    assert_eq!(clone.put(nz(1), 'B'), b);
+    assert_eq!(clone.key_of(&'X'), None);
+    // The original is unaffected by mutations of its clone.
+    assert_eq!(original.key_of(&'A'), Some(a));
+    assert_eq!(original.key_of(&'C'), None);
+    assert_eq!(original.get(b), Some((2, &'B')));
+    // Both are internally consistent.
+    for indexed in [&original, &clone] {
+        assert_eq!(indexed.iter().count(), indexed.len());
+        for (key, _, item) in indexed.iter() {
+            assert_eq!(indexed.key_of(item), Some(key));
+        }
+    }
+}
+
+#[test]
+fn drain_sorted_by_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(5), 'B'), (nz(1), 'C'), (nz(2), 'D')]);
+    let drained = stash
+        .drain_sorted_by(|(lhs, _), (rhs, _)| rhs.cmp(lhs))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        drained,
+        [
+            (Key(1), 5, 'B'),
+            (Key(0), 2, 'A'),
+            (Key(3), 2, 'D'),
+            (Key(2), 1, 'C'),
+        ]
+    );
+    assert!(stash.is_empty());
+    assert_eq!(stash.len_items(), 0);
+    assert_eq!(stash.put(nz(1), 'E'), Key(0));
+}
+
+#[test]
+fn from_iter_reserves_upfront() {
+    let stash = (0..10_000).map(|n| (nz(1), n)).collect::<MultiStash<i32>>();
+    assert_eq!(stash.len(), 10_000);
+    assert_eq!(stash.capacity(), 10_000);
+}
+
+#[test]
+fn vacant_count_works() {
+    let mut stash = <MultiStash<char>>::new();
+    assert_eq!(stash.vacant_count(), 0);
+    stash.extend("ABCDE".chars().map(|c| (nz(1), c)));
+    assert_eq!(stash.vacant_count(), 0);
+    assert!(stash.take_all(Key(1)).is_some());
+    assert!(stash.take_all(Key(3)).is_some());
+    assert_eq!(stash.vacant_count(), 2);
+    // Removing the last element also reclaims the vacant entry before it.
+    assert!(stash.take_all(Key(4)).is_some());
+    assert_eq!(stash.vacant_count(), 1);
+    assert_eq!(stash.put(nz(1), 'F'), Key(1));
+    assert_eq!(stash.vacant_count(), 0);
+}
+
+#[test]
+fn dense_multi_stash_works() {
+    let mut stash = <DenseMultiStash<char>>::new();
+    let mut mirror = Vec::new();
+    for c in "ABCD".chars() {
+        stash.put(nz(1), c);
+        mirror.push(c);
+    }
+    assert_eq!(stash.dense_keys(), [Key(0), Key(1), Key(2), Key(3)]);
+    let (remaining, item, swap) = stash.take_all_dense(Key(1)).unwrap();
+    assert_eq!((remaining, item), (1, 'B'));
+    assert_eq!(swap.removed(), 1);
+    assert_eq!(swap.moved_from(), Some(3));
+    mirror.swap_remove(swap.removed());
+    assert_eq!(mirror, ['A', 'D', 'C']);
+    assert_eq!(stash.dense_keys(), [Key(0), Key(3), Key(2)]);
+    assert_eq!(stash.dense_position(Key(3)), Some(1));
+    // Removing the last element in dense order moves nothing.
+    let (_, item, swap) = stash.take_all_dense(Key(2)).unwrap();
+    assert_eq!(item, 'C');
+    assert_eq!(swap.removed(), 2);
+    assert_eq!(swap.moved_from(), None);
+    assert_eq!(stash.take_all_dense(Key(2)), None);
+    assert_eq!(stash.put(nz(2), 'E'), Key(2));
+    assert_eq!(stash.dense_position(Key(2)), Some(2));
+    for (position, key) in stash.dense_keys().iter().enumerate() {
+        assert_eq!(stash.dense_position(*key), Some(position));
+    }
+    assert_eq!(stash.len(), 3);
+    assert_eq!(stash.len_items(), 4);
+}
+
+#[test]
+fn shrink_to_fit_works() {
+    let mut stash = <MultiStash<char>>::with_capacity(16);
+    assert_eq!(stash.capacity(), 16);
+    stash.reserve(4);
+    assert_eq!(stash.capacity(), 16);
+    stash.extend("ABCD".chars().map(|c| (nz(1), c)));
+    assert!(!stash.exact_capacity());
+    assert!(stash.take_all(Key(1)).is_some());
+    assert!(stash.take_all(Key(3)).is_some());
+    stash.shrink_to_fit();
+    assert!(stash.exact_capacity());
+    assert_eq!(stash.capacity(), 3);
+    assert_eq!(stash.put(nz(1), 'E'), Key(1));
+    let mut stash = <MultiStash<char>>::new();
+    stash.shrink_to_fit();
+    assert!(stash.exact_capacity());
+}
+
+#[test]
+fn enumerate_occupied_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABCD".chars().map(|c| (nz(1), c)));
+    assert!(stash.take_all(Key(0)).is_some());
+    assert!(stash.take_all(Key(2)).is_some());
+    assert_eq!(
+        stash.enumerate_occupied().collect::<Vec<_>>(),
+        [(0, Key(1), 1, &'B'), (1, Key(3), 1, &'D')]
+    );
+    for (rank, key, _, _) in stash.enumerate_occupied() {
+        assert_eq!(stash.key_at_rank(rank), Some(key));
+    }
+}
+
+#[test]
+fn take_all_detailed_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABCD".chars().map(|c| (nz(2), c)));
+    let taken = stash.take_all_detailed(Key(1)).unwrap();
+    assert_eq!(taken.remaining(), 2);
+    assert_eq!(taken.item(), &'B');
+    assert_eq!(taken.became_free_index(), 1);
+    assert_eq!(taken.into_item(), 'B');
+    assert!(stash.take_all(Key(2)).is_some());
+    // Removing the last element reclaims all vacant entries at the end.
+    let taken = stash.take_all_detailed(Key(3)).unwrap();
+    assert_eq!(taken.became_free_index(), 1);
+    assert_eq!(stash.take_all_detailed(Key(3)), None);
+    let taken = stash.take_all_detailed(Key(0)).unwrap();
+    assert_eq!(taken.became_free_index(), 0);
+    assert_eq!(stash.put(nz(1), 'E'), Key(taken.became_free_index()));
+}
+
+#[test]
+fn put_entry_works() {
+    let mut stash = <MultiStash<Vec<char>>>::new();
+    let key = stash.put_entry(nz(1), vec!['A']).bump(5).key();
+    assert_eq!(key, Key(0));
+    assert_eq!(stash.get(key), Some((6, &vec!['A'])));
+    let mut handle = stash.put_entry(nz(2), vec!['B']);
+    handle.item_mut().push('C');
+    assert_eq!(handle.key(), Key(1));
+    assert_eq!(handle.remaining(), 2);
+    assert_eq!(handle.item(), &['B', 'C']);
+    assert_eq!(stash.len_items(), 8);
+}
+
+#[test]
+fn retain_compact_works() {
+    let mut stash = <MultiStash<u32>>::new();
+    stash.extend((0..8).map(|n| (nz(1 + n as usize), n)));
+    assert!(stash.take_all(Key(1)).is_some());
+    let remap = stash.retain_compact(|_, _, item| {
+        *item *= 10;
+        *item % 20 == 0
+    });
+    assert_eq!(
+        remap,
+        [(Key(2), Key(1)), (Key(4), Key(2)), (Key(6), Key(3))]
+    );
+    assert_eq!(
+        stash.iter().collect::<Vec<_>>(),
+        [
+            (Key(0), 1, &0),
+            (Key(1), 3, &20),
+            (Key(2), 5, &40),
+            (Key(3), 7, &60),
+        ]
+    );
+    assert_eq!(stash.len_items(), 16);
+    assert_eq!(stash.max_key(), Some(Key(3)));
+    assert_eq!(stash.put(nz(1), 70), Key(4));
+    assert!(stash.retain_compact(|_, _, _| false).is_empty());
+    assert!(stash.is_empty());
+    assert_eq!(stash.len_items(), 0);
+}
+
+#[test]
+fn retain_compact_panic() {
+    let mut stash = <MultiStash<u32>>::new();
+    stash.extend((0..6).map(|n| (nz(1), n)));
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        stash.retain_compact(|_, _, item| match *item {
+            4 => panic!("oops"),
+            n => n % 2 == 1,
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(stash.len(), 4);
+    assert_eq!(stash.len_items(), 4);
+    assert_eq!(stash.min_key(), Some(Key(0)));
+    assert_eq!(stash.max_key(), Some(Key(5)));
+    assert_eq!(
+        stash.iter().map(|(_, _, item)| *item).collect::<Vec<_>>(),
+        [1, 3, 4, 5]
+    );
+    assert_eq!(stash.put(nz(1), 6), Key(2));
+    assert_eq!(stash.put(nz(1), 7), Key(3));
+    assert_eq!(stash.put(nz(1), 8), Key(6));
+}
+
+#[test]
+fn extend_dense_panic() {
+    let mut stash = MultiStashBuilder::new()
+        .with_max_per_entry(nz(5))
+        .build::<char>();
+    stash.extend("ABCD".chars().map(|c| (nz(1), c)));
+    assert!(stash.take_all(Key(0)).is_some());
+    assert!(stash.take_all(Key(2)).is_some());
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        stash.extend_dense([(nz(1), 'X'), (nz(9), 'Y')]);
+    }));
+    assert!(result.is_err());
+    assert!(stash.check_invariants());
+    assert_eq!(stash.len(), 3);
+    assert_eq!(stash.len_items(), 3);
+    assert_eq!(stash.get(Key(0)), Some((1, &'X')));
+    assert_eq!(stash.put(nz(1), 'E'), Key(2));
+    assert_eq!(stash.put(nz(1), 'F'), Key(4));
+    assert_eq!(stash.get(Key(0)), Some((1, &'X')));
+}
+
+#[test]
+fn split_at_key_works() {
+    let mut stash = <MultiStash<u32>>::new();
+    stash.extend((0..6).map(|n| (nz(1), n)));
+    assert!(stash.take_all(Key(1)).is_some());
+    let (mut lhs, mut rhs) = stash.split_at_key(Key(3));
+    assert_eq!(lhs.len(), 2);
+    assert_eq!(rhs.len(), 3);
+    assert_eq!(lhs.get_mut(Key(3)), None);
+    assert_eq!(rhs.get_mut(Key(2)), None);
+    assert_eq!(rhs.get_mut(Key(4)), Some((1, &mut 4)));
+    for (_, _, item) in lhs.iter_mut() {
+        *item += 10;
+    }
+    for (key, _, item) in rhs.iter_mut() {
+        *item += key.0 as u32 * 100;
+    }
+    assert_eq!(
+        stash.iter().map(|(_, _, item)| *item).collect::<Vec<_>>(),
+        [10, 12, 303, 404, 505]
+    );
+    let (lhs, rhs) = stash.split_at_key(Key(100));
+    assert_eq!(lhs.len(), 5);
+    assert!(rhs.is_empty());
+}
+
+#[test]
+fn growth_increment_works() {
+    let mut stash = MultiStashBuilder::new()
+        .with_growth_increment(nz(4))
+        .build::<u32>();
+    assert_eq!(stash.growth_increment(), Some(nz(4)));
+    assert_eq!(stash.capacity(), 0);
+    for n in 0..9 {
+        stash.put(nz(1), n);
+        assert_eq!(stash.capacity(), (n as usize / 4 + 1) * 4);
+    }
+    assert!(stash.take_all(Key(3)).is_some());
+    stash.put(nz(1), 9);
+    assert_eq!(stash.capacity(), 12);
+    assert_eq!(stash.insert_at(13, nz(1), 13), None);
+    assert_eq!(stash.capacity(), 16);
+    let mut other = <MultiStash<u32>>::new();
+    other.insert_at(18, nz(1), 18);
+    stash.merge_with(other, |_, _, _, _| {});
+    assert_eq!(stash.len_entries(), 19);
+    assert_eq!(stash.capacity(), 20);
+    assert!(stash.check_invariants());
+    assert_eq!(<MultiStash<u32>>::new().growth_increment(), None);
+}
+
+#[test]
+fn iter_debug_works() {
+    use std::format;
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(1), 'A'), (nz(2), 'B'), (nz(3), 'C')]);
+    assert!(stash.take_all(Key(1)).is_some());
+    let mut iter = stash.iter();
+    assert_eq!(format!("{iter:?}"), "[(Key(0), 1, 'A'), (Key(2), 3, 'C')]");
+    assert!(iter.next().is_some());
+    assert_eq!(format!("{iter:?}"), "[(Key(2), 3, 'C')]");
+    assert_eq!(
+        format!("{:?}", stash.iter_mut()),
+        "IterMut { remaining: 2, .. }"
+    );
+    assert_eq!(
+        format!("{:?}", stash.into_iter()),
+        "IntoIter { remaining: 2, .. }"
+    );
+}
+
+#[test]
+fn from_iter_rev_works() {
+    let mut stash = MultiStash::from_iter_rev([(nz(1), 'A'), (nz(2), 'B'), (nz(3), 'C')]);
+    assert_eq!(stash.len(), 3);
+    assert_eq!(stash.len_items(), 6);
+    assert_eq!(stash.min_key(), Some(Key(0)));
+    assert_eq!(stash.max_key(), Some(Key(2)));
+    assert_eq!(stash[Key(0)], 'C');
+    assert_eq!(stash.put(nz(1), 'D'), Key(3));
+    assert_eq!(
+        stash.into_iter().collect::<Vec<_>>(),
+        [
+            (Key(0), 3, 'C'),
+            (Key(1), 2, 'B'),
+            (Key(2), 1, 'A'),
+            (Key(3), 1, 'D'),
+        ]
+    );
+    let stash = MultiStash::<char>::from_iter_rev([]);
+    assert!(stash.is_empty());
+    assert_eq!(stash.max_key(), None);
+}
+
+#[test]
+fn try_extend_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.put(nz(usize::MAX - 10), 'A');
+    let mut iter = [(nz(5), 'B'), (nz(5), 'C'), (nz(1), 'D'), (nz(1), 'E')].into_iter();
+    assert_eq!(stash.try_extend(iter.by_ref()), Err((2, (nz(1), 'D'))));
+    assert_eq!(iter.next(), Some((nz(1), 'E')));
+    assert_eq!(stash.len(), 3);
+    assert_eq!(stash.len_items(), usize::MAX);
+    let mut stash = MultiStashBuilder::new()
+        .with_max_per_entry(nz(3))
+        .build::<char>();
+    assert_eq!(
+        stash.try_extend([(nz(1), 'A'), (nz(3), 'B'), (nz(4), 'C'), (nz(1), 'D')]),
+        Err((2, (nz(4), 'C')))
+    );
+    assert_eq!(stash.len_items(), 4);
+    assert_eq!(stash.try_extend([(nz(2), 'E'), (nz(3), 'F')]), Ok(2));
+    assert_eq!(stash.len_items(), 9);
+}
+
+#[test]
+fn is_valid_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABC".chars().map(|c| (nz(1), c)));
+    assert!(stash.take_all(Key(1)).is_some());
+    for index in 0..5 {
+        let key = Key(index);
+        assert_eq!(stash.is_valid(key), stash.get(key).is_some());
+    }
+    assert!(stash.is_valid(Key(0)));
+    assert!(!stash.is_valid(Key(1)));
+    assert!(!stash.is_valid(Key(3)));
+}
+
+#[test]
+fn swap_take_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABC".chars().map(|c| (nz(2), c)));
+    assert!(stash.take_all(Key(1)).is_some());
+    assert_eq!(stash.swap_take(Key(0), nz(5), 'X'), Some((2, 'A')));
+    assert_eq!(stash.swap_take(Key(1), nz(1), 'Y'), None);
+    assert_eq!(stash.swap_take(Key(4), nz(1), 'Z'), None);
+    assert_eq!(stash.len(), 4);
+    assert_eq!(stash.len_items(), 9);
+    assert_eq!(stash.get(Key(0)), Some((5, &'X')));
+    assert_eq!(stash.get(Key(1)), Some((1, &'Y')));
+    assert_eq!(stash.put(nz(1), 'W'), Key(3));
+}
+
+#[test]
+fn chain_tagged_works() {
+    let a = [(nz(1), 'A'), (nz(2), 'B')]
+        .into_iter()
+        .collect::<MultiStash<char>>();
+    let b = [(nz(3), 'C')].into_iter().collect::<MultiStash<char>>();
+    assert_eq!(
+        MultiStash::chain_tagged(&a, &b).collect::<Vec<_>>(),
+        [
+            (false, Key(0), 1, &'A'),
+            (false, Key(1), 2, &'B'),
+            (true, Key(0), 3, &'C'),
+        ]
+    );
+}
+
+#[test]
+fn items_in_range_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(1), 'A'), (nz(2), 'B'), (nz(3), 'C'), (nz(4), 'D')]);
+    assert!(stash.take_all(Key(1)).is_some());
+    assert_eq!(stash.items_in_range(Key(0), Key(4)), stash.len_items());
+    assert_eq!(stash.items_in_range(Key(0), Key(2)), 1);
+    assert_eq!(stash.items_in_range(Key(1), Key(3)), 3);
+    assert_eq!(stash.items_in_range(Key(2), Key(100)), 7);
+    assert_eq!(stash.items_in_range(Key(3), Key(2)), 0);
+    assert_eq!(stash.items_in_range(Key(50), Key(100)), 0);
+}
+
+#[test]
+fn into_parts_roundtrip_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABCD".chars().map(|c| (nz(2), c)));
+    assert!(stash.take_all(Key(0)).is_some());
+    assert!(stash.take_all(Key(2)).is_some());
+    let expected = stash
+        .iter()
+        .map(|(key, n, c)| (key, n, *c))
+        .collect::<Vec<_>>();
+    let (entries, free, len_items, len_occupied) = stash.into_parts();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(free, 2);
+    assert_eq!(len_items, 4);
+    assert_eq!(len_occupied, 2);
+    // Safety: the raw parts stem from a valid `MultiStash`.
+    let mut stash =
+        unsafe { MultiStash::from_parts_unchecked(entries, free, len_items, len_occupied) };
+    assert_eq!(
+        stash
+            .iter()
+            .map(|(key, n, c)| (key, n, *c))
+            .collect::<Vec<_>>(),
+        expected
+    );
+    assert_eq!(stash.min_key(), Some(Key(1)));
+    assert_eq!(stash.max_key(), Some(Key(3)));
+    assert_eq!(stash.put(nz(1), 'E'), Key(2));
+    assert_eq!(stash.put(nz(1), 'F'), Key(0));
+}
+
+#[test]
+fn clone_subset_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([
+        (nz(1), 'A'),
+        (nz(2), 'B'),
+        (nz(3), 'C'),
+        (nz(4), 'D'),
+        (nz(5), 'E'),
+    ]);
+    assert!(stash.take_all(Key(2)).is_some());
+    let mut subset = stash.clone_subset(&[Key(3), Key(1), Key(2), Key(3), Key(100)]);
+    assert_eq!(
+        subset.iter().collect::<Vec<_>>(),
+        [(Key(1), 2, &'B'), (Key(3), 4, &'D')]
+    );
+    assert_eq!(subset.len(), 2);
+    assert_eq!(subset.len_items(), 6);
+    assert_eq!(subset.min_key(), Some(Key(1)));
+    assert_eq!(subset.max_key(), Some(Key(3)));
+    assert_eq!(subset.put(nz(1), 'X'), Key(0));
+    assert_eq!(subset.put(nz(1), 'Y'), Key(2));
+    assert_eq!(subset.put(nz(1), 'Z'), Key(4));
+    assert_eq!(stash.len(), 4);
+    assert!(stash.clone_subset(&[Key(2)]).is_empty());
+}
+
+#[test]
+fn index_mut_assign_keeps_counts() {
+    let mut stash = <MultiStash<char>>::new();
+    let a = stash.put(nz(3), 'A');
+    let b = stash.put(nz(2), 'B');
+    stash[a] = 'X';
+    stash[b.0] = 'Y';
+    assert_eq!(stash.get(a), Some((3, &'X')));
+    assert_eq!(stash.get(b), Some((2, &'Y')));
+    assert_eq!(stash.len(), 2);
+    assert_eq!(stash.len_items(), 5);
+}
+
+#[test]
+fn reserve_each_works() {
+    let mut stash = <MultiStash<Vec<u8>>>::new();
+    stash.extend((0..3).map(|_| (nz(1), Vec::new())));
+    assert!(stash.take_all(Key(1)).is_some());
+    stash.reserve_each(|v| v.reserve(16));
+    for (_, _, v) in stash.iter() {
+        assert!(v.capacity() >= 16);
+    }
+    assert_eq!(stash.len(), 2);
+}
+
+#[test]
+fn take_if_works() {
+    let mut stash = <MultiStash<char>>::new();
+    let a = stash.put(nz(3), 'A');
+    let b = stash.put(nz(1), 'B');
+    assert_eq!(stash.take_if(a, |remaining, _| remaining < 3), None);
+    assert_eq!(stash.get(a), Some((3, &'A')));
+    assert_eq!(stash.take_if(a, |_, item| *item == 'A'), Some((3, 'A')));
+    assert_eq!(stash.take_if(a, |_, _| true), None);
+    assert_eq!(stash.take_if(Key(5), |_, _| true), None);
+    assert_eq!(stash.len_items(), 1);
+    assert_eq!(stash.take_if(b, |_, _| true), Some((1, 'B')));
+    assert!(stash.is_empty());
+}
+
+#[test]
+fn bump_if_works() {
+    let mut stash = <MultiStash<char>>::new();
+    let a = stash.put(nz(2), 'A');
+    let below_threshold = |remaining: usize, _: &char| remaining < 5;
+    assert_eq!(stash.bump_if(a, 4, below_threshold), Some(2));
+    assert_eq!(stash.bump_if(a, 4, below_threshold), None);
+    assert_eq!(stash.get(a), Some((6, &'A')));
+    assert_eq!(stash.len_items(), 6);
+    assert_eq!(stash.bump_if(Key(1), 1, |_, _| true), None);
+    assert_eq!(stash.len_items(), 6);
+}
+
+#[test]
+fn check_invariants_works() {
+    let mut stash = <MultiStash<char>>::new();
+    assert!(stash.check_invariants());
+    stash.extend("ABCDEF".chars().map(|c| (nz(2), c)));
+    assert!(stash.take_all(Key(1)).is_some());
+    assert!(stash.take_all(Key(4)).is_some());
+    assert_eq!(stash.take_one(Key(2)), Some((1, 'C')));
+    assert!(stash.check_invariants());
+    assert!(stash.take_all(Key(5)).is_some());
+    stash.put(nz(1), 'G');
+    assert!(stash.check_invariants());
+}
+
+#[test]
+#[cfg(feature = "raw")]
+fn entries_mut_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABCD".chars().map(|c| (nz(2), c)));
+    // Safety: the invariants are restored below before any other method is called.
+    let entries = unsafe { stash.entries_mut() };
+    entries[1] = Entry::from(VacantEntry::new(4));
+    assert!(!stash.check_invariants());
+    stash.free = 1;
+    stash.len_items -= 2;
+    stash.len_occupied -= 1;
+    assert!(stash.check_invariants());
+    assert_eq!(stash.put(nz(1), 'E'), Key(1));
+}
+
+#[test]
+fn recompute_counters_repairs_parts() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABCD".chars().map(|c| (nz(2), c)));
+    let (mut entries, _, _, _) = stash.into_parts();
+    entries.swap(0, 3);
+    entries[1] = Entry::from(VacantEntry::new(0));
+    entries[3] = Entry::from(VacantEntry::new(0));
+    // Safety: the invariants are restored below before any other method is called.
+    let mut stash = unsafe { MultiStash::from_parts_unchecked(entries, 0, 0, 0) };
+    assert!(!stash.check_invariants());
+    stash.recompute_counters();
+    stash.rebuild_free_list();
+    assert!(stash.check_invariants());
+    assert_eq!(stash.len(), 2);
+    assert_eq!(stash.len_items(), 4);
+    assert_eq!(stash.min_key(), Some(Key(0)));
+    assert_eq!(stash.max_key(), Some(Key(2)));
+    assert_eq!(stash.put(nz(1), 'E'), Key(1));
+    assert_eq!(stash.put(nz(1), 'F'), Key(3));
+    assert_eq!(stash.put(nz(1), 'G'), Key(4));
+}
+
+#[test]
+#[cfg(feature = "raw")]
+fn recompute_counters_repairs_raw_edits() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend("ABCDEF".chars().map(|c| (nz(1), c)));
+    // Safety: the invariants are restored below before any other method is called.
+    let entries = unsafe { stash.entries_mut() };
+    for entry in entries.iter_mut().step_by(2) {
+        *entry = Entry::from(VacantEntry::new(0));
+    }
+    entries.reverse();
+    stash.recompute_counters();
+    stash.rebuild_free_list();
+    assert!(stash.check_invariants());
+    assert_eq!(
+        stash.iter().collect::<Vec<_>>(),
+        [(Key(0), 1, &'F'), (Key(2), 1, &'D'), (Key(4), 1, &'B')]
+    );
+    assert_eq!(stash.put(nz(1), 'G'), Key(1));
+}
+
+#[test]
+fn iter_copied_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(1), 'A'), (nz(2), 'B'), (nz(3), 'C')]);
+    assert!(stash.take_all(Key(1)).is_some());
+    assert_eq!(
+        stash.iter_copied().collect::<Vec<_>>(),
+        [(Key(0), 1, 'A'), (Key(2), 3, 'C')]
+    );
+    assert_eq!(stash.iter_copied().next_back(), Some((Key(2), 3, 'C')));
+}
+
+#[test]
+fn new_does_not_allocate() {
+    let mut stash = <MultiStash<char>>::new();
+    assert!(!stash.has_allocated());
+    assert_eq!(stash.get(Key(0)), None);
+    assert_eq!(stash.iter().count(), 0);
+    assert_eq!(stash.take_all(Key(0)), None);
+    assert_eq!(stash.next_free_key(), Key(0));
+    stash.reserve(0);
+    assert!(!stash.has_allocated());
+    stash.put(nz(1), 'A');
+    assert!(stash.has_allocated());
+    assert!(!<MultiStash<char>>::with_capacity(0).has_allocated());
+}
+
+#[test]
+fn take_at_works() {
+    let mut stash = <MultiStash<char>>::new();
+    stash.extend([(nz(2), 'A'), (nz(3), 'B')]);
+    assert_eq!(stash.take_one_at(1), Some((2, 'B')));
+    assert_eq!(stash.take_all_at(0), Some((2, 'A')));
+    assert_eq!(stash.take_all_at(0), None);
+    assert_eq!(stash.take_one_at(5), None);
+    assert_eq!(stash.len_items(), 2);
+}
+
+#[test]
+fn shrink_to_fit_reporting_works() {
+    let mut stash = <MultiStash<u64>>::with_capacity(16);
+    stash.extend((0..4).map(|n| (nz(1), n)));
+    assert!(stash.take_all(Key(3)).is_some());
+    assert_eq!(
+        stash.shrink_to_fit_reporting(),
+        13 * core::mem::size_of::<Entry<u64>>()
+    );
+    assert!(stash.exact_capacity());
+    assert_eq!(stash.shrink_to_fit_reporting(), 0);
+}